@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+/// @notice Public output of the zkTLS program.
+struct GuestOutput {
+    // Keccak-256 of the HTTP request sent to the server.
+    bytes32 requestHash;
+    // `target.client` of the request.
+    address client;
+    // `target.prover_id` of the request.
+    bytes32 proverId;
+    // `target.submit_network_id` of the request.
+    uint64 submitNetworkId;
+    // Time of the response, in seconds since the Unix epoch.
+    uint64 responseTime;
+    // Parts of the response selected by the response template.
+    bytes[] responses;
+}
+
+/// @title Decoding of the zkTLS public output
+/// @notice The output is the ABI encoding of a `GuestOutput` tuple, as written
+/// by `abi.encode(output)`.
+library ZkTLSOutput {
+    /// @notice Decodes the public values or journal of a zkTLS proof.
+    function decode(bytes calldata output) internal pure returns (GuestOutput memory) {
+        return abi.decode(output, (GuestOutput));
+    }
+
+    /// @notice SHA-256 of the public values or journal of a zkTLS proof, the
+    /// same digest for both backends. It is the journal digest RISC0 verifies.
+    function digest(bytes calldata output) internal pure returns (bytes32) {
+        return sha256(output);
+    }
+}
@@ -0,0 +1,9 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.9;
+
+// Generated by `zktls export-verifier` from the risc0-zkvm Groth16 verifier parameters.
+library ControlID {
+    bytes32 public constant CONTROL_ROOT = hex"{{CONTROL_ROOT}}";
+    // NOTE: This has opposite byte order to the value in the risc0 repository.
+    bytes32 public constant BN254_CONTROL_ID = hex"{{BN254_CONTROL_ID}}";
+}
@@ -0,0 +1,51 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+import {IRiscZeroVerifier} from "./IRiscZeroVerifier.sol";
+import {GuestOutput, ZkTLSOutput} from "./ZkTLSOutput.sol";
+
+/// @title zkTLS verifier for the RISC0 backend
+/// @notice Verifies proofs of the zkTLS program {{PROGRAM_VERSION}}.
+contract ZkTLSVerifier {
+    /// @notice Image ID of the zkTLS program.
+    bytes32 public constant IMAGE_ID = {{IMAGE_ID}};
+
+    /// @notice RISC0 verifier, `RiscZeroGroth16Verifier` or a RISC0 verifier router.
+    IRiscZeroVerifier public immutable verifier;
+
+    constructor(address verifier_) {
+        verifier = IRiscZeroVerifier(verifier_);
+    }
+
+    /// @notice Verifies a zkTLS proof, reverts if it is invalid.
+    /// @param journal The journal committed by the zkTLS program.
+    /// @param seal The Groth16 seal, prefixed with the verifier selector.
+    /// @return The verified journal.
+    function verify(bytes calldata journal, bytes calldata seal)
+        external
+        view
+        returns (bytes calldata)
+    {
+        verifier.verify(seal, IMAGE_ID, ZkTLSOutput.digest(journal));
+        return journal;
+    }
+
+    /// @notice Verifies a zkTLS proof and decodes its journal, reverts if it is invalid.
+    /// @param journal The journal committed by the zkTLS program.
+    /// @param seal The Groth16 seal, prefixed with the verifier selector.
+    /// @return The verified output of the zkTLS program.
+    function verifyAndDecode(bytes calldata journal, bytes calldata seal)
+        external
+        view
+        returns (GuestOutput memory)
+    {
+        verifier.verify(seal, IMAGE_ID, ZkTLSOutput.digest(journal));
+        return ZkTLSOutput.decode(journal);
+    }
+
+    /// @notice SHA-256 of the journal, to store or compare a verified response.
+    /// @dev See `ZkTLSOutput.digest`, the SP1 verifier returns the same digest.
+    function outputDigest(bytes calldata journal) external pure returns (bytes32) {
+        return ZkTLSOutput.digest(journal);
+    }
+}
@@ -0,0 +1,51 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+import {ISP1Verifier} from "./ISP1Verifier.sol";
+import {GuestOutput, ZkTLSOutput} from "./ZkTLSOutput.sol";
+
+/// @title zkTLS verifier for the SP1 backend
+/// @notice Verifies proofs of the zkTLS program {{PROGRAM_VERSION}}.
+contract ZkTLSVerifier {
+    /// @notice Verification key of the zkTLS program.
+    bytes32 public constant PROGRAM_VKEY = {{PROGRAM_VKEY}};
+
+    /// @notice SP1 verifier, `SP1VerifierGroth16` or a SP1 verifier gateway.
+    ISP1Verifier public immutable verifier;
+
+    constructor(address verifier_) {
+        verifier = ISP1Verifier(verifier_);
+    }
+
+    /// @notice Verifies a zkTLS proof, reverts if it is invalid.
+    /// @param publicValues The public values committed by the zkTLS program.
+    /// @param proof The proof bytes, prefixed with the verifier selector.
+    /// @return The verified public values.
+    function verify(bytes calldata publicValues, bytes calldata proof)
+        external
+        view
+        returns (bytes calldata)
+    {
+        verifier.verifyProof(PROGRAM_VKEY, publicValues, proof);
+        return publicValues;
+    }
+
+    /// @notice Verifies a zkTLS proof and decodes its public values, reverts if it is invalid.
+    /// @param publicValues The public values committed by the zkTLS program.
+    /// @param proof The proof bytes, prefixed with the verifier selector.
+    /// @return The verified output of the zkTLS program.
+    function verifyAndDecode(bytes calldata publicValues, bytes calldata proof)
+        external
+        view
+        returns (GuestOutput memory)
+    {
+        verifier.verifyProof(PROGRAM_VKEY, publicValues, proof);
+        return ZkTLSOutput.decode(publicValues);
+    }
+
+    /// @notice SHA-256 of the public values, to store or compare a verified response.
+    /// @dev See `ZkTLSOutput.digest`, the RISC0 verifier returns the same digest.
+    function outputDigest(bytes calldata publicValues) external pure returns (bytes32) {
+        return ZkTLSOutput.digest(publicValues);
+    }
+}
@@ -0,0 +1,51 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+import {Test} from "forge-std/Test.sol";
+
+import {GuestOutput, ZkTLSOutput} from "../ZkTLSOutput.sol";
+
+contract ZkTLSOutputTest is Test {
+    /// @dev `ZkTLSOutput.decode` reads calldata, it is called through this contract.
+    function decode(bytes calldata output) external pure returns (GuestOutput memory) {
+        return ZkTLSOutput.decode(output);
+    }
+
+    /// @dev `ZkTLSOutput.digest` reads calldata, it is called through this contract.
+    function digest(bytes calldata output) external pure returns (bytes32) {
+        return ZkTLSOutput.digest(output);
+    }
+
+    /// @dev Same output as `test_guest_output_fixture` of the zktls binary.
+    function test_decode() public view {
+        bytes memory fixture = vm.parseBytes(vm.readLine("test/fixtures/guest_output.hex"));
+
+        GuestOutput memory output = this.decode(fixture);
+
+        assertEq(output.requestHash, keccak256("GET / HTTP/1.1\r\nHost: example.com\r\n\r\n"));
+        assertEq(output.client, 0x1111111111111111111111111111111111111111);
+        assertEq(output.proverId, bytes32(0x2222222222222222222222222222222222222222222222222222222222222222));
+        assertEq(output.submitNetworkId, 1);
+        assertEq(output.responseTime, 1_700_000_000);
+        assertEq(output.responses.length, 2);
+        assertEq(output.responses[0], bytes("HTTP/1.1 200 OK"));
+        assertEq(output.responses[1], bytes('{"price":42}'));
+    }
+
+    /// @dev Same digest as `test_guest_output_fixture` of the zktls binary.
+    function test_digest() public view {
+        bytes memory fixture = vm.parseBytes(vm.readLine("test/fixtures/guest_output.hex"));
+
+        assertEq(this.digest(fixture), bytes32(0x49a786847d52165e5ccaf7ebfcf7a9824b789c309b563e5910edca888c4d8097));
+    }
+
+    function test_decode_truncated() public {
+        bytes memory fixture = vm.parseBytes(vm.readLine("test/fixtures/guest_output.hex"));
+        assembly {
+            mstore(fixture, sub(mload(fixture), 32))
+        }
+
+        vm.expectRevert();
+        this.decode(fixture);
+    }
+}
@@ -0,0 +1,117 @@
+use std::path::PathBuf;
+
+use alloy_primitives::{keccak256, B256};
+use anyhow::{anyhow, Result};
+use clap::Args;
+use tokio::fs;
+use zktls_core::{
+    merkle_root, AggregationCommitment, ArtifactFormat, ProofArtifact, ProofMode, ProofSystem,
+};
+
+use crate::error::CliError;
+
+use super::prover::ProverArgs;
+
+#[derive(Args)]
+pub struct AggregateArgs {
+    /// Proof artifacts to aggregate, generated with `--proof-mode compressed`
+    #[arg(required = true)]
+    pub artifacts: Vec<PathBuf>,
+
+    #[command(flatten)]
+    pub prover: ProverArgs,
+
+    /// Use this aggregation program instead of the one of the registry, built
+    /// from `program/sp1/aggregate-sp1` or `program/risc0/aggregate-r0`
+    #[arg(long)]
+    pub aggregation_program: Option<PathBuf>,
+
+    /// What the aggregated proof commits: merkle-root or journals
+    #[arg(long, default_value_t = AggregationCommitment::MerkleRoot)]
+    pub commitment: AggregationCommitment,
+
+    /// Kind of the aggregated proof
+    #[arg(long, default_value_t = ProofMode::Groth16)]
+    pub proof_mode: ProofMode,
+
+    /// Write the aggregated proof artifact to this file, as CBOR if the extension is `.cbor`, JSON otherwise
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+impl AggregateArgs {
+    pub async fn execute(&self) -> Result<()> {
+        let mut proofs = Vec::with_capacity(self.artifacts.len());
+        let mut request_hashes = Vec::with_capacity(self.artifacts.len());
+
+        for path in &self.artifacts {
+            let artifact = ProofArtifact::load(path).map_err(CliError::Request)?;
+
+            if artifact.backend != self.prover.prover.backend()
+                || artifact.proof_system != ProofSystem::Compressed
+            {
+                return Err(CliError::Request(anyhow!(
+                    "{} is a {:?} proof of {:?}, expected a compressed proof of {:?}",
+                    path.display(),
+                    artifact.proof_system,
+                    artifact.backend,
+                    self.prover.prover.backend()
+                ))
+                .into());
+            }
+
+            request_hashes.extend_from_slice(artifact.request_hash.as_slice());
+            proofs.push(artifact.output());
+        }
+
+        let public_outputs: Vec<_> = proofs.iter().map(|p| p.public_output.clone()).collect();
+
+        let program = self.prover.load_program().await?;
+        let aggregation_program = match &self.aggregation_program {
+            Some(path) => fs::read(path)
+                .await
+                .map_err(|e| CliError::Program(e.into()))?,
+            None => {
+                self.prover
+                    .program
+                    .load_release(self.prover.prover.aggregation_program_type())
+                    .await?
+            }
+        };
+
+        let output = self
+            .prover
+            .aggregate(
+                proofs,
+                &program,
+                &aggregation_program,
+                self.commitment,
+                self.proof_mode,
+            )
+            .await?;
+
+        // The aggregated proof has no request of its own, it is traced back to
+        // the hashes of the requests of its proofs.
+        let request_hash: B256 = keccak256(&request_hashes);
+        let mut artifact = ProofArtifact::new(output, request_hash);
+
+        if self.commitment == AggregationCommitment::MerkleRoot {
+            let root = merkle_root(&public_outputs);
+            log::info!("merkle root: {}", root);
+            artifact.merkle_root = Some(root);
+        }
+
+        match &self.output {
+            Some(path) => {
+                artifact.save(path)?;
+                log::info!("aggregated proof artifact written to {}", path.display());
+            }
+            None => {
+                let json = artifact.encode(ArtifactFormat::Json)?;
+                println!("{}", String::from_utf8(json)?);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,369 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use alloy_primitives::B256;
+use anyhow::Result;
+use clap::Args;
+use futures_util::{future, stream, StreamExt};
+use serde::Serialize;
+use tokio::{fs, sync::mpsc};
+use zktls_core::{wait_for_provers, ArtifactFormat, ProofArtifact, ProofMode};
+use zktls_input_builder::{TLSInputBuilderConfig, TemplatedRequest};
+use zktls_program_core::GuestInput;
+
+use crate::error::CliError;
+
+use super::{
+    input::{build_request_input, request_hash},
+    prover::ProverArgs,
+};
+
+/// Arguments to prove many requests at once.
+#[derive(Args)]
+pub struct BatchArgs {
+    /// Prove every request of this JSONL file, one request per line
+    #[arg(long, conflicts_with_all = ["input_request_file", "record", "replay", "hex"])]
+    pub batch: Option<PathBuf>,
+
+    /// Number of inputs built at the same time in batch mode
+    #[arg(long, default_value_t = 8)]
+    pub input_concurrency: usize,
+
+    /// Number of proofs generated at the same time in batch mode, raise it for network provers
+    #[arg(long, default_value_t = 1)]
+    pub prove_concurrency: usize,
+}
+
+/// Outcome of a request of the batch.
+#[derive(Serialize)]
+struct RequestReport {
+    /// Line of the request in the batch file, from 1.
+    line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_hash: Option<B256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    artifact: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorReport>,
+    input_ms: u128,
+    proving_ms: u128,
+}
+
+#[derive(Serialize)]
+struct ErrorReport {
+    kind: &'static str,
+    message: String,
+}
+
+impl From<anyhow::Error> for ErrorReport {
+    fn from(e: anyhow::Error) -> Self {
+        let e = CliError::from(e);
+
+        Self {
+            kind: e.kind(),
+            message: e.message(),
+        }
+    }
+}
+
+/// Written to `report.json` in the output directory.
+#[derive(Serialize)]
+struct BatchReport {
+    total: usize,
+    succeeded: usize,
+    failed: usize,
+    elapsed_ms: u128,
+    requests: Vec<RequestReport>,
+}
+
+impl BatchReport {
+    fn new(mut requests: Vec<RequestReport>, elapsed: Duration) -> Self {
+        requests.sort_by_key(|report| report.line);
+
+        let failed = requests
+            .iter()
+            .filter(|report| report.error.is_some())
+            .count();
+
+        Self {
+            total: requests.len(),
+            succeeded: requests.len() - failed,
+            failed,
+            elapsed_ms: elapsed.as_millis(),
+            requests,
+        }
+    }
+}
+
+impl RequestReport {
+    fn new(line: usize) -> Self {
+        Self {
+            line,
+            request_hash: None,
+            artifact: None,
+            error: None,
+            input_ms: 0,
+            proving_ms: 0,
+        }
+    }
+
+    fn fail(mut self, e: anyhow::Error) -> Self {
+        self.error = Some(e.into());
+        self
+    }
+}
+
+impl BatchArgs {
+    /// Prove the requests of the batch file into `output_dir`.
+    ///
+    /// Inputs are built `input_concurrency` at a time and each one is proved as
+    /// soon as it is ready, `prove_concurrency` at a time. The TLS calls don't
+    /// wait on the proofs, and a slow server only delays its own request.
+    pub async fn prove(
+        &self,
+        path: &Path,
+        config: &TLSInputBuilderConfig,
+        prover: &ProverArgs,
+        mode: ProofMode,
+        output_dir: &Path,
+    ) -> Result<()> {
+        let start = Instant::now();
+
+        let requests = read_batch(path).await.map_err(CliError::Request)?;
+        let program = prover.load_program().await?;
+        fs::create_dir_all(output_dir).await?;
+
+        log::info!("proving {} requests", requests.len());
+        let (built, mut ready) = mpsc::unbounded_channel();
+
+        let build_inputs = stream::iter(requests)
+            .map(|(line, request)| async move {
+                let report = RequestReport::new(line);
+                let request = match request {
+                    Ok(request) => request,
+                    Err(e) => return (report.fail(CliError::Request(e).into()), None),
+                };
+
+                // The hash identifies the proof, a request without one isn't proved.
+                let request_hash = match request_hash(&request) {
+                    Ok(request_hash) => request_hash,
+                    Err(e) => return (report.fail(CliError::Request(e).into()), None),
+                };
+                let mut report = RequestReport {
+                    request_hash: Some(request_hash),
+                    ..report
+                };
+
+                let begin = Instant::now();
+                let input = build_request_input(config.clone(), request).await;
+                report.input_ms = begin.elapsed().as_millis();
+
+                match input {
+                    Ok(input) => (report, Some((input, request_hash))),
+                    Err(e) => {
+                        log::warn!("line {}: {:#}", line, e);
+                        (report.fail(e), None)
+                    }
+                }
+            })
+            .buffer_unordered(self.input_concurrency.max(1))
+            .for_each(move |input| {
+                // The receiver lives until every input is proved.
+                let _ = built.send(input);
+                future::ready(())
+            });
+
+        let prove_concurrency = self.prove_concurrency.max(1);
+        let prove_inputs = stream::poll_fn(|cx| ready.poll_recv(cx))
+            .map(|(report, input)| {
+                let program = &program;
+                async move {
+                    match input {
+                        Some((built, request_hash)) => {
+                            // A proof abandoned after its deadline keeps its slot until it exits.
+                            wait_for_provers(prove_concurrency).await;
+                            let proof = ProofRequest {
+                                input: built.input,
+                                trusted_root: built.trusted_root,
+                                request_hash,
+                                mode,
+                            };
+                            prove_request(report, proof, prover, program, output_dir).await
+                        }
+                        None => report,
+                    }
+                }
+            })
+            .buffer_unordered(prove_concurrency)
+            .collect::<Vec<_>>();
+
+        // Both run in this task, the proofs themselves on their own threads.
+        let ((), reports) = tokio::join!(build_inputs, prove_inputs);
+        let report = BatchReport::new(reports, start.elapsed());
+
+        let report_path = output_dir.join("report.json");
+        fs::write(&report_path, serde_json::to_vec_pretty(&report)?).await?;
+
+        println!(
+            "Proved {} of {} requests in {:?}, report written to {}",
+            report.succeeded,
+            report.total,
+            Duration::from_millis(report.elapsed_ms as u64),
+            report_path.display()
+        );
+
+        if report.failed > 0 {
+            return Err(CliError::BatchFailed {
+                failed: report.failed,
+                total: report.total,
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Built input of a request, ready to be proved.
+struct ProofRequest {
+    input: GuestInput,
+    trusted_root: Option<B256>,
+    request_hash: B256,
+    mode: ProofMode,
+}
+
+async fn prove_request(
+    mut report: RequestReport,
+    request: ProofRequest,
+    prover: &ProverArgs,
+    program: &[u8],
+    output_dir: &Path,
+) -> RequestReport {
+    log::info!("proving line {}", report.line);
+
+    let begin = Instant::now();
+    let output = prover.prove(request.input, program, request.mode).await;
+    report.proving_ms = begin.elapsed().as_millis();
+
+    let output = match output {
+        Ok(output) => output,
+        Err(e) => {
+            log::warn!("line {}: {:#}", report.line, e);
+            return report.fail(e);
+        }
+    };
+
+    let mut artifact = ProofArtifact::new(output, request.request_hash);
+    artifact.trusted_root = request.trusted_root;
+    let path = output_dir.join(format!("request-{}.json", report.line));
+
+    match save_artifact(&artifact, &path).await {
+        Ok(()) => {
+            report.artifact = Some(path);
+            report
+        }
+        Err(e) => report.fail(e),
+    }
+}
+
+async fn save_artifact(artifact: &ProofArtifact, path: &Path) -> Result<()> {
+    fs::write(path, artifact.encode(ArtifactFormat::Json)?).await?;
+
+    Ok(())
+}
+
+/// Requests of a JSONL file with their line number, skipping empty lines.
+async fn read_batch(path: &Path) -> Result<Vec<(usize, Result<TemplatedRequest>)>> {
+    let content = fs::read_to_string(path).await?;
+
+    Ok(content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| (i + 1, serde_json::from_str(line).map_err(Into::into)))
+        .collect())
+}
+
+#[cfg(all(test, feature = "sp1-backend"))]
+mod tests {
+    use std::env;
+
+    use clap::Parser;
+    use serde_json::Value;
+
+    use super::*;
+
+    #[derive(Parser)]
+    struct TestCli {
+        #[command(flatten)]
+        prover: ProverArgs,
+    }
+
+    #[tokio::test]
+    async fn test_batch_report() {
+        let dir = env::temp_dir().join(format!("zktls-batch-{}", std::process::id()));
+        fs::create_dir_all(&dir).await.unwrap();
+
+        // Nothing listens on port 1, so the TLS call of the request fails.
+        let mut request: Value =
+            serde_json::from_str(include_str!("../../../../testdata/input.json")).unwrap();
+        request["request_info"]["remote_addr"] = "127.0.0.1:1".into();
+
+        let batch = dir.join("requests.jsonl");
+        fs::write(&batch, format!("{{invalid\n\n{}\n", request))
+            .await
+            .unwrap();
+
+        let program = dir.join("program");
+        fs::write(&program, b"elf").await.unwrap();
+        let prover = TestCli::parse_from([
+            "zktls",
+            "--mock",
+            "--prover",
+            "sp1",
+            "--program",
+            program.to_str().unwrap(),
+        ])
+        .prover;
+
+        let args = BatchArgs {
+            batch: Some(batch.clone()),
+            input_concurrency: 2,
+            prove_concurrency: 1,
+        };
+        let output_dir = dir.join("proofs");
+        let e = args
+            .prove(
+                &batch,
+                &TLSInputBuilderConfig::default(),
+                &prover,
+                ProofMode::Groth16,
+                &output_dir,
+            )
+            .await
+            .unwrap_err();
+
+        let e = CliError::from(e);
+        assert_eq!(e.exit_code(), 10);
+        assert_eq!(e.to_string(), "2 of 2 requests failed");
+
+        let report: Value =
+            serde_json::from_slice(&fs::read(output_dir.join("report.json")).await.unwrap())
+                .unwrap();
+        assert_eq!(report["total"], 2);
+        assert_eq!(report["succeeded"], 0);
+        assert_eq!(report["failed"], 2);
+
+        let requests = &report["requests"];
+        assert_eq!(requests[0]["line"], 1);
+        assert_eq!(requests[0]["error"]["kind"], "request");
+        assert!(requests[0].get("request_hash").is_none());
+        assert_eq!(requests[1]["line"], 3);
+        assert_eq!(requests[1]["error"]["kind"], "tls");
+        assert!(requests[1]["request_hash"].is_string());
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
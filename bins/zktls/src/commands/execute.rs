@@ -0,0 +1,70 @@
+use std::path::PathBuf;
+
+use alloy_primitives::hex;
+use anyhow::Result;
+use clap::Args;
+use zktls_core::{Backend, ZkExecutor};
+
+use crate::error::CliError;
+
+use super::{input::InputArgs, program::ProgramArgs, types::Prover};
+
+#[derive(Args)]
+pub struct ExecuteArgs {
+    /// Path to the input request file
+    #[arg(short, long)]
+    pub input_request_file: PathBuf,
+
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Prover backend whose executor is used
+    #[arg(short, long, value_enum)]
+    pub prover: Prover,
+
+    #[command(flatten)]
+    pub program: ProgramArgs,
+}
+
+impl ExecuteArgs {
+    pub async fn execute(&self) -> Result<()> {
+        let (built, _) = self.input.build_input(&self.input_request_file).await?;
+        let input = built.input;
+
+        let program = self.program.load(self.prover.program_type()).await?;
+
+        let output = match self.prover {
+            #[cfg(feature = "r0-backend")]
+            Prover::R0 => {
+                zktls_guest_prover_r0::Risc0GuestProver::default()
+                    .execute(input, &program)
+                    .await
+            }
+            #[cfg(feature = "sp1-backend")]
+            Prover::Sp1 => {
+                zktls_guest_prover_sp1::SP1GuestProver::new(None)
+                    .execute(input, &program)
+                    .await
+            }
+        }
+        .map_err(CliError::prover)?;
+
+        println!(
+            "public output: {}",
+            hex::encode_prefixed(&output.public_output)
+        );
+        println!("total cycles: {}", output.total_cycles);
+        if let Some(user_cycles) = output.user_cycles {
+            println!("user cycles: {}", user_cycles);
+        }
+        match output.backend {
+            Backend::Sp1 => println!("shards: {}", output.segments),
+            Backend::R0 => println!("segments: {}", output.segments),
+        }
+        for (region, cycles) in &output.cycle_tracker {
+            println!("cycles of {}: {}", region, cycles);
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,20 @@
-use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "r0-backend")]
+use alloy_primitives::B256;
+use anyhow::{anyhow, Result};
 use clap::Args;
+#[cfg(feature = "r0-backend")]
+use sha2::{Digest, Sha256};
+use tokio::fs;
+
+use super::{
+    program::ProgramArgs,
+    types::{Prover, TargetChain},
+};
 
-use super::types::{Prover, TargetChain};
+/// Decoding of the public output, shared by the verifiers of both backends.
+const ZKTLS_OUTPUT: &str = include_str!("../../contracts/ZkTLSOutput.sol");
 
 #[derive(Args)]
 pub struct ExportVerifierArgs {
@@ -12,17 +25,286 @@ pub struct ExportVerifierArgs {
     /// Prover backend to use
     #[arg(long, value_enum)]
     pub prover: Prover,
+
+    /// Directory to write the verifier contracts to
+    #[arg(short, long, default_value = "contracts")]
+    pub output_dir: PathBuf,
+
+    /// Read the risc0-ethereum contracts from this directory, `contracts/src`
+    /// of a checkout of its release, instead of downloading them
+    #[cfg(feature = "r0-backend")]
+    #[arg(long)]
+    pub risc0_contracts: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub program: ProgramArgs,
 }
 
 impl ExportVerifierArgs {
-    #[allow(clippy::unnecessary_wraps)]
-    // Result type is kept for future implementation that may return errors
-    pub fn execute(&self) -> Result<()> {
+    pub async fn execute(&self) -> Result<()> {
+        if !matches!(self.target_chain, TargetChain::Evm) {
+            return Err(anyhow!(
+                "Exporting a verifier for {:?} is not supported yet",
+                self.target_chain
+            ));
+        }
+
+        fs::create_dir_all(self.output_dir.join("groth16")).await?;
+        fs::write(self.output_dir.join("ZkTLSOutput.sol"), ZKTLS_OUTPUT).await?;
+
+        match self.prover {
+            #[cfg(feature = "sp1-backend")]
+            Prover::Sp1 => export_sp1(&self.output_dir, &self.program).await?,
+            #[cfg(feature = "r0-backend")]
+            Prover::R0 => {
+                export_r0(
+                    &self.output_dir,
+                    &self.program,
+                    self.risc0_contracts.as_deref(),
+                )
+                .await?
+            }
+        }
+
         println!(
-            "Exporting verifier for target chain: {:?} using prover: {:?}",
-            self.target_chain, self.prover
+            "Verifier contracts written to {}, deploy `ZkTLSVerifier` with the address of the Groth16 verifier",
+            self.output_dir.display()
         );
-        // TODO: Implement verifier export logic
+
         Ok(())
     }
 }
+
+#[cfg(feature = "sp1-backend")]
+async fn export_sp1(output_dir: &Path, program: &ProgramArgs) -> Result<()> {
+    const ISP1_VERIFIER: &str = include_str!("../../contracts/sp1/ISP1Verifier.sol");
+    const ZKTLS_VERIFIER: &str = include_str!("../../contracts/sp1/ZkTLSVerifier.sol");
+
+    let version = &program.program_version;
+    let program = program.load("sp1").await?;
+    let vkey = zktls_guest_prover_sp1::program_vkey(&program);
+    log::info!("program vkey: {}", vkey);
+
+    // The Groth16 verifier of the installed SP1 version is shipped with its
+    // circuit artifacts, so it always matches the proofs of this binary.
+    let artifacts_dir =
+        tokio::task::spawn_blocking(zktls_guest_prover_sp1::groth16_artifacts_dir).await?;
+
+    let mut copied = 0;
+    let mut entries = fs::read_dir(&artifacts_dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "sol") {
+            fs::copy(&path, output_dir.join("groth16").join(entry.file_name())).await?;
+            copied += 1;
+        }
+    }
+    if copied == 0 {
+        return Err(anyhow!(
+            "No Solidity verifier found in {}",
+            artifacts_dir.display()
+        ));
+    }
+
+    fs::write(output_dir.join("ISP1Verifier.sol"), ISP1_VERIFIER).await?;
+    fs::write(
+        output_dir.join("ZkTLSVerifier.sol"),
+        render(ZKTLS_VERIFIER, version, &[("PROGRAM_VKEY", &vkey)]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Release of risc0-ethereum matching the risc0-zkvm version of the prover.
+#[cfg(feature = "r0-backend")]
+const RISC0_ETHEREUM_URL: &str =
+    "https://raw.githubusercontent.com/risc0/risc0-ethereum/v1.2.0/contracts/src";
+
+/// Contracts of risc0-ethereum the RISC0 verifier is built from.
+#[cfg(feature = "r0-backend")]
+const RISC0_CONTRACTS: &[&str] = &[
+    "IRiscZeroVerifier.sol",
+    "IRiscZeroSelectable.sol",
+    "StructHash.sol",
+    "Util.sol",
+    "groth16/Groth16Verifier.sol",
+    "groth16/RiscZeroGroth16Verifier.sol",
+];
+
+/// SHA-256 digests of [`RISC0_CONTRACTS`] in the release of
+/// [`RISC0_ETHEREUM_URL`], as in `("Util.sol", b256!("<sha256 of Util.sol>"))`.
+///
+/// The contracts missing here are refused, unless unpinned programs are
+/// allowed: they are then exported as they are.
+#[cfg(feature = "r0-backend")]
+const RISC0_CONTRACT_DIGESTS: &[(&str, B256)] = &[];
+
+/// Check a contract of risc0-ethereum against its digest in `digests`.
+#[cfg(feature = "r0-backend")]
+fn check_contract(
+    digests: &[(&str, B256)],
+    contract: &str,
+    source: &str,
+    allow_unpinned: bool,
+) -> Result<()> {
+    let digest = B256::from_slice(&Sha256::digest(source));
+
+    match digests.iter().find(|(c, _)| *c == contract) {
+        Some((_, pinned)) if *pinned != digest => Err(anyhow!(
+            "contract {} has sha256 {}, expected {}",
+            contract,
+            digest,
+            pinned
+        )),
+        Some(_) => Ok(()),
+        None if allow_unpinned => {
+            log::warn!(
+                "contract {} has no pinned digest, sha256 {}",
+                contract,
+                digest
+            );
+            Ok(())
+        }
+        None => Err(anyhow!(
+            "contract {} has no pinned digest, pass --allow-unpinned to export it anyway",
+            contract
+        )),
+    }
+}
+
+#[cfg(feature = "r0-backend")]
+async fn export_r0(
+    output_dir: &Path,
+    program: &ProgramArgs,
+    contracts_dir: Option<&Path>,
+) -> Result<()> {
+    use alloy_primitives::hex;
+
+    const CONTROL_ID: &str = include_str!("../../contracts/r0/ControlID.sol");
+    const ZKTLS_VERIFIER: &str = include_str!("../../contracts/r0/ZkTLSVerifier.sol");
+
+    let allow_unpinned = program.registry.allow_unpinned;
+    let version = &program.program_version;
+    let program = program.load("r0").await?;
+    let image_id = zktls_guest_prover_r0::image_id(&program)?;
+    log::info!("program image id: 0x{}", hex::encode(image_id));
+
+    for contract in RISC0_CONTRACTS {
+        let source = match contracts_dir {
+            Some(dir) => fs::read_to_string(dir.join(contract)).await?,
+            None => {
+                let url = format!("{}/{}", RISC0_ETHEREUM_URL, contract);
+                log::info!("downloading contract from {}", url);
+                reqwest::get(&url).await?.error_for_status()?.text().await?
+            }
+        };
+        check_contract(RISC0_CONTRACT_DIGESTS, contract, &source, allow_unpinned)?;
+        fs::write(output_dir.join(contract), source).await?;
+    }
+
+    // The control IDs are generated from the verifier parameters of the
+    // prover, instead of the ones in the downloaded release.
+    let (control_root, bn254_control_id) = zktls_guest_prover_r0::groth16_control_ids();
+    fs::write(
+        output_dir.join("groth16").join("ControlID.sol"),
+        render(
+            CONTROL_ID,
+            version,
+            &[
+                ("CONTROL_ROOT", &hex::encode(control_root)),
+                ("BN254_CONTROL_ID", &hex::encode(bn254_control_id)),
+            ],
+        ),
+    )
+    .await?;
+
+    fs::write(
+        output_dir.join("ZkTLSVerifier.sol"),
+        render(
+            ZKTLS_VERIFIER,
+            version,
+            &[("IMAGE_ID", &format!("0x{}", hex::encode(image_id)))],
+        ),
+    )
+    .await?;
+
+    println!("The RISC0 verifier depends on OpenZeppelin contracts, add them to your project");
+
+    Ok(())
+}
+
+fn render(template: &str, version: &str, values: &[(&str, &str)]) -> String {
+    let mut rendered = template.replace("{{PROGRAM_VERSION}}", version);
+    for (name, value) in values {
+        rendered = rendered.replace(&format!("{{{{{}}}}}", name), value);
+    }
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{address, b256, hex, keccak256, B256};
+    use sha2::{Digest, Sha256};
+    use zktls_core::GuestOutput;
+
+    /// Output decoded by `contracts/test/ZkTLSOutput.t.sol`.
+    const FIXTURE: &str = include_str!("../../contracts/test/fixtures/guest_output.hex");
+
+    #[test]
+    fn test_guest_output_fixture() {
+        let output = GuestOutput {
+            requestHash: keccak256(b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n"),
+            client: address!("1111111111111111111111111111111111111111"),
+            proverId: b256!("2222222222222222222222222222222222222222222222222222222222222222"),
+            submitNetworkId: 1,
+            responseTime: 1_700_000_000,
+            responses: vec![
+                b"HTTP/1.1 200 OK".to_vec().into(),
+                br#"{"price":42}"#.to_vec().into(),
+            ],
+        };
+
+        let fixture = hex::decode(FIXTURE.trim()).unwrap();
+        assert_eq!(hex::encode(output.encode()), hex::encode(&fixture));
+        assert_eq!(GuestOutput::decode(&fixture).unwrap(), output);
+
+        // `ZkTLSVerifier.outputDigest` of both backends.
+        assert_eq!(
+            B256::from_slice(&Sha256::digest(&fixture)),
+            b256!("49a786847d52165e5ccaf7ebfcf7a9824b789c309b563e5910edca888c4d8097")
+        );
+
+        assert!(GuestOutput::decode(&fixture[..fixture.len() - 32]).is_err());
+    }
+
+    #[cfg(feature = "r0-backend")]
+    #[test]
+    fn test_check_contract() {
+        use super::check_contract;
+
+        let source = "contract Util {}";
+        let digests = [("Util.sol", B256::from_slice(&Sha256::digest(source)))];
+
+        check_contract(&digests, "Util.sol", source, false).unwrap();
+        assert!(check_contract(&digests, "Util.sol", "contract Other {}", true).is_err());
+
+        assert!(check_contract(&digests, "StructHash.sol", source, false).is_err());
+        check_contract(&digests, "StructHash.sol", source, true).unwrap();
+    }
+
+    #[cfg(feature = "r0-backend")]
+    #[test]
+    #[ignore = "the digests of the risc0-ethereum v1.2.0 contracts are not pinned yet"]
+    fn test_risc0_contracts_pinned() {
+        use super::{RISC0_CONTRACTS, RISC0_CONTRACT_DIGESTS};
+
+        for contract in RISC0_CONTRACTS {
+            assert!(
+                RISC0_CONTRACT_DIGESTS.iter().any(|(c, _)| c == contract),
+                "contract {} has no pinned digest",
+                contract
+            );
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use alloy_primitives::{keccak256, B256};
+use anyhow::Result;
+use clap::Args;
+use zktls_input_builder::{
+    RootSource, TLSInputBuilder, TLSInputBuilderConfig, TemplatedRequest, Transcript,
+};
+use zktls_program_core::GuestInput;
+
+use crate::error::CliError;
+
+/// Arguments to build the guest input of a request, whose file is an argument
+/// of each command.
+#[derive(Args)]
+pub struct InputArgs {
+    /// Save the TLS transcript of the request to this file
+    #[arg(long, conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Build the input from a recorded TLS transcript instead of calling the server
+    #[arg(long)]
+    pub replay: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub roots: RootArgs,
+}
+
+/// Root certificates trusted by TLS calls.
+#[derive(Args)]
+pub struct RootArgs {
+    /// Trust the root certificates of this PEM bundle
+    #[arg(long)]
+    pub ca_file: Vec<PathBuf>,
+
+    /// Trust the root certificates of the operating system
+    #[arg(long, default_value_t = false)]
+    pub native_roots: bool,
+
+    /// Don't trust the bundled webpki root certificates
+    #[arg(long, default_value_t = false)]
+    pub no_webpki_roots: bool,
+
+    /// Only trust the root certificates with these SHA-256 SPKI fingerprints for
+    /// TLS calls, proofs don't record them
+    #[arg(long)]
+    pub allow_root: Vec<B256>,
+}
+
+impl RootArgs {
+    pub fn config(&self) -> TLSInputBuilderConfig {
+        let mut roots = Vec::new();
+        if !self.no_webpki_roots {
+            roots.push(RootSource::WebPki);
+        }
+        if self.native_roots {
+            roots.push(RootSource::Native);
+        }
+        roots.extend(self.ca_file.iter().cloned().map(RootSource::PemFile));
+
+        let mut config = TLSInputBuilderConfig::default().roots(roots);
+        for fingerprint in &self.allow_root {
+            config = config.allow_root(*fingerprint);
+        }
+
+        config
+    }
+}
+
+impl InputArgs {
+    /// Build the guest input, and the hash of the request it was built from.
+    pub async fn build_input(&self, path: &Path) -> Result<(BuiltInput, B256)> {
+        let request = read_request(path).map_err(CliError::Request)?;
+        let request_hash = request_hash(&request)?;
+
+        let mut config = self.roots.config();
+        if let Some(path) = &self.record {
+            config = config.record_transcript(path);
+        }
+
+        let input = match &self.replay {
+            Some(path) => {
+                let transcript = Transcript::load(path).map_err(CliError::Request)?;
+                let mut builder = TLSInputBuilder::with_config(config).map_err(CliError::Tls)?;
+                let input = builder
+                    .build_replayed_input(request, transcript)
+                    .map_err(CliError::input)?;
+                BuiltInput {
+                    input,
+                    trusted_root: builder.trusted_root(),
+                }
+            }
+            None => build_request_input(config, request).await?,
+        };
+
+        Ok((input, request_hash))
+    }
+}
+
+fn read_request(path: &Path) -> Result<TemplatedRequest> {
+    // Validate that input file exists
+    if !path.exists() {
+        return Err(anyhow::anyhow!(
+            "Input request file does not exist: {}",
+            path.display()
+        ));
+    }
+
+    let input_request_file = fs::read_to_string(path)?;
+
+    Ok(serde_json::from_str(&input_request_file)?)
+}
+
+/// Guest input of a request, with the root its server was trusted by.
+pub struct BuiltInput {
+    pub input: GuestInput,
+    /// See [`TLSInputBuilder::trusted_root`].
+    pub trusted_root: Option<B256>,
+}
+
+/// Build the guest input of a request by calling its server.
+pub async fn build_request_input(
+    config: TLSInputBuilderConfig,
+    request: TemplatedRequest,
+) -> Result<BuiltInput> {
+    let mut builder = TLSInputBuilder::with_config(config).map_err(CliError::Tls)?;
+
+    let input = builder
+        .build_templated_input(request)
+        .await
+        .map_err(CliError::input)?;
+
+    Ok(BuiltInput {
+        input,
+        trusted_root: builder.trusted_root(),
+    })
+}
+
+/// Keccak-256 of the JSON request, identifies the request of a proof artifact.
+pub fn request_hash(request: &TemplatedRequest) -> Result<B256> {
+    Ok(keccak256(serde_json::to_vec(request)?))
+}
@@ -1,6 +1,21 @@
+mod aggregate;
+mod batch;
+mod execute;
 mod export_verifier;
+mod input;
+mod program;
 mod prove;
+mod prover;
+mod request;
+mod serve;
 mod types;
+mod verify;
 
+pub use aggregate::AggregateArgs;
+pub use execute::ExecuteArgs;
 pub use export_verifier::ExportVerifierArgs;
+pub use program::ProgramCommandArgs;
 pub use prove::ProveArgs;
+pub use request::RequestArgs;
+pub use serve::ServeArgs;
+pub use verify::VerifyArgs;
@@ -0,0 +1,208 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use tokio::fs;
+
+use crate::{
+    error::CliError,
+    program::{program_digest, ProgramRegistry, DEFAULT_MIRROR, PROGRAM_VERSION},
+};
+
+use super::types::Prover;
+
+/// Where the program registry is and how it downloads programs.
+#[derive(Args)]
+pub struct RegistryArgs {
+    /// Directory of the program registry, `~/.local/zktlsd` by default
+    #[arg(long, env = "ZKTLS_PROGRAM_DIR")]
+    pub program_dir: Option<PathBuf>,
+
+    /// Mirror to download the programs from
+    #[arg(long, env = "ZKTLS_PROGRAM_MIRROR", default_value = DEFAULT_MIRROR)]
+    pub program_mirror: String,
+
+    /// Never download programs
+    #[arg(long, default_value_t = false)]
+    pub offline: bool,
+
+    /// Trust the programs of releases without a pinned digest on first download
+    #[arg(long, default_value_t = false)]
+    pub allow_unpinned: bool,
+}
+
+impl RegistryArgs {
+    pub fn registry(&self) -> Result<ProgramRegistry> {
+        let registry = match &self.program_dir {
+            Some(dir) => ProgramRegistry::new(dir),
+            None => ProgramRegistry::from_home()?,
+        };
+
+        Ok(registry
+            .mirror(&self.program_mirror)
+            .offline(self.offline)
+            .allow_unpinned(self.allow_unpinned))
+    }
+}
+
+/// Which program to run.
+#[derive(Args)]
+pub struct ProgramArgs {
+    /// Use this program instead of the one of the registry
+    #[arg(long)]
+    pub program: Option<PathBuf>,
+
+    /// Release of the program
+    #[arg(long, default_value = PROGRAM_VERSION)]
+    pub program_version: String,
+
+    #[command(flatten)]
+    pub registry: RegistryArgs,
+}
+
+impl ProgramArgs {
+    pub async fn load(&self, ty: &str) -> Result<Vec<u8>> {
+        Ok(self.read(ty).await.map_err(CliError::Program)?)
+    }
+
+    async fn read(&self, ty: &str) -> Result<Vec<u8>> {
+        if let Some(path) = &self.program {
+            let program = fs::read(path).await?;
+            log::info!(
+                "using program {} with sha256 {}",
+                path.display(),
+                program_digest(&program)
+            );
+            return Ok(program);
+        }
+
+        self.read_release(ty).await
+    }
+
+    /// Load a program of the release from the registry, even if `--program` is
+    /// set, as the aggregation programs are.
+    pub async fn load_release(&self, ty: &str) -> Result<Vec<u8>> {
+        Ok(self.read_release(ty).await.map_err(CliError::Program)?)
+    }
+
+    async fn read_release(&self, ty: &str) -> Result<Vec<u8>> {
+        self.registry
+            .registry()?
+            .get(ty, &self.program_version)
+            .await
+    }
+}
+
+#[derive(Args)]
+pub struct ProgramCommandArgs {
+    #[command(subcommand)]
+    pub command: ProgramCommands,
+}
+
+#[derive(Subcommand)]
+pub enum ProgramCommands {
+    /// List the programs of the registry
+    List(ProgramListArgs),
+
+    /// Download a program into the registry
+    Fetch(ProgramFetchArgs),
+
+    /// Copy a program into the registry, for machines without network access
+    Import(ProgramImportArgs),
+}
+
+#[derive(Args)]
+pub struct ProgramListArgs {
+    #[command(flatten)]
+    pub registry: RegistryArgs,
+}
+
+#[derive(Args)]
+pub struct ProgramFetchArgs {
+    /// Prover backend of the program
+    #[arg(short, long, value_enum)]
+    pub prover: Prover,
+
+    /// Fetch the aggregation program of the backend instead of the zkTLS program
+    #[arg(long, default_value_t = false)]
+    pub aggregation: bool,
+
+    /// Release of the program
+    #[arg(long, default_value = PROGRAM_VERSION)]
+    pub version: String,
+
+    #[command(flatten)]
+    pub registry: RegistryArgs,
+}
+
+#[derive(Args)]
+pub struct ProgramImportArgs {
+    /// Path to the program
+    pub path: PathBuf,
+
+    /// Prover backend of the program
+    #[arg(short, long, value_enum)]
+    pub prover: Prover,
+
+    /// Import the program as the aggregation program of the backend
+    #[arg(long, default_value_t = false)]
+    pub aggregation: bool,
+
+    /// Release of the program
+    #[arg(long, default_value = PROGRAM_VERSION)]
+    pub version: String,
+
+    #[command(flatten)]
+    pub registry: RegistryArgs,
+}
+
+impl ProgramCommandArgs {
+    pub async fn execute(&self) -> Result<()> {
+        match &self.command {
+            ProgramCommands::List(args) => {
+                let registry = args.registry.registry()?;
+
+                for entry in registry.list().await? {
+                    let pinned = match entry.pinned {
+                        Some(true) => "pinned",
+                        Some(false) => "MISMATCH",
+                        None => "unpinned",
+                    };
+                    println!(
+                        "{} {} {} {} {}",
+                        entry.version,
+                        entry.ty,
+                        entry.digest,
+                        pinned,
+                        entry.path.display()
+                    );
+                }
+                for ty in registry.embedded() {
+                    println!("{} {} embedded", PROGRAM_VERSION, ty);
+                }
+            }
+            ProgramCommands::Fetch(args) => {
+                let path = args
+                    .registry
+                    .registry()?
+                    .fetch(args.prover.registry_type(args.aggregation), &args.version)
+                    .await?;
+                println!("{}", path.display());
+            }
+            ProgramCommands::Import(args) => {
+                let entry = args
+                    .registry
+                    .registry()?
+                    .import(
+                        args.prover.registry_type(args.aggregation),
+                        &args.version,
+                        &args.path,
+                    )
+                    .await?;
+                println!("{} {}", entry.path.display(), entry.digest);
+            }
+        }
+
+        Ok(())
+    }
+}
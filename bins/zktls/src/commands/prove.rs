@@ -1,123 +1,94 @@
-use std::{fs, path::PathBuf};
+use std::path::PathBuf;
 
-use crate::utils;
-
-use super::types::{Prover, TargetChain};
+use super::{batch::BatchArgs, input::InputArgs, prover::ProverArgs, types::TargetChain};
 
+use alloy_primitives::hex;
 use anyhow::Result;
 use clap::Args;
-use zktls_core::InputBuilder;
-use zktls_core::ZkProver;
-use zktls_input_builder::TLSInputBuilder;
-use zktls_program_core::Request;
+use zktls_core::{ArtifactFormat, ProofArtifact, ProofMode};
 
 #[derive(Args)]
 pub struct ProveArgs {
     /// Path to the input request file
-    #[arg(short, long)]
-    pub input_request_file: PathBuf,
+    #[arg(short, long, required_unless_present = "batch")]
+    pub input_request_file: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub input: InputArgs,
 
     /// Target chain for the proof
     #[arg(short, long, value_enum)]
     pub target_chain: TargetChain,
 
-    /// Use mock mode
-    #[arg(long, group = "proverMode", default_value_t = false)]
-    pub mock: bool,
-
-    /// Use local mode
-    #[arg(long, group = "proverMode", default_value_t = false)]
-    pub local: bool,
+    #[command(flatten)]
+    pub prover: ProverArgs,
 
-    /// Use cuda mode
-    #[cfg(feature = "_cuda")]
-    #[arg(long, group = "proverMode", default_value_t = false)]
-    pub cuda: bool,
+    /// Kind of proof to generate: core, compressed, groth16 or plonk
+    #[arg(long, default_value_t = ProofMode::Groth16)]
+    pub proof_mode: ProofMode,
 
-    /// Use network mode
-    #[arg(long, group = "proverMode", default_value_t = false)]
-    pub network: bool,
+    /// Write the proof artifact to this file, as CBOR if the extension is `.cbor`, JSON otherwise.
+    /// In batch mode, the directory of the artifacts and the report, `proofs` by default
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
 
-    /// Prover backend to use
-    #[arg(short, long, value_enum)]
-    pub prover: Prover,
+    /// Print the public output and the proof as hex, one per line
+    #[arg(long, default_value_t = false)]
+    pub hex: bool,
 
-    /// Moongate server to use
-    #[arg(short, long, env = "SP1_MOONGATE_SERVER")]
-    pub sp1_moongate_server: Option<String>,
+    #[command(flatten)]
+    pub batch: BatchArgs,
 }
 
 impl ProveArgs {
     pub async fn execute(&self) -> Result<()> {
-        // Validate that input file exists
-        if !self.input_request_file.exists() {
-            return Err(anyhow::anyhow!(
-                "Input request file does not exist: {}",
-                self.input_request_file.display()
-            ));
+        if let Some(path) = &self.batch.batch {
+            let output_dir = self.output.clone().unwrap_or_else(|| "proofs".into());
+
+            return self
+                .batch
+                .prove(
+                    path,
+                    &self.input.roots.config(),
+                    &self.prover,
+                    self.proof_mode,
+                    &output_dir,
+                )
+                .await;
+        }
+
+        let path = self.input_request_file.as_ref().expect("required by clap");
+        let (built, request_hash) = self.input.build_input(path).await?;
+
+        let program = self.prover.load_program().await?;
+        let output = self
+            .prover
+            .prove(built.input, &program, self.proof_mode)
+            .await?;
+
+        if output.is_mock() {
+            log::warn!("mock mode, the artifact has no proof");
         }
 
-        let input_request_file = fs::read_to_string(&self.input_request_file)?;
-        let request: Request = serde_json::from_str(&input_request_file)?;
-
-        let mut builder = TLSInputBuilder::new().unwrap();
-        match builder.build_input(request).await {
-            Ok(input) => {
-                let output = match self.prover {
-                    #[cfg(feature = "r0-backend")]
-                    Prover::R0 => {
-                        let mut guest = zktls_guest_prover_r0::Risc0GuestProver::default();
-
-                        if self.mock {
-                            guest = guest.mock();
-                        }
-
-                        if self.local {
-                            guest = guest.local();
-                        }
-
-                        #[cfg(feature = "r0-backend-cuda")]
-                        if self.cuda {
-                            guest = guest.cuda();
-                        }
-
-                        if self.network {
-                            guest = guest.network();
-                        }
-
-                        let program = utils::get_program("r0").await?;
-                        guest.prove(input, &program).await?
-                    }
-                    #[cfg(feature = "sp1-backend")]
-                    Prover::Sp1 => {
-                        let mut guest = zktls_guest_prover_sp1::SP1GuestProver::new(
-                            self.sp1_moongate_server.clone(),
-                        );
-                        if self.mock {
-                            guest = guest.mock();
-                        }
-                        if self.local {
-                            guest = guest.local();
-                        }
-                        #[cfg(feature = "sp1-backend-cuda")]
-                        if self.cuda {
-                            guest = guest.cuda();
-                        }
-                        if self.network {
-                            guest = guest.network();
-                        }
-                        let program = utils::get_program("sp1").await?;
-                        guest.prove(input, &program).await?
-                    }
-                };
-
-                println!("output: {:?}", output.0);
-                println!("proof: {:?}", output.1);
-            }
-            Err(e) => {
-                println!("Error: {:?}", e);
-            }
+        let mut artifact = ProofArtifact::new(output, request_hash);
+        artifact.trusted_root = built.trusted_root;
+
+        if let Some(path) = &self.output {
+            artifact.save(path)?;
+            log::info!("proof artifact written to {}", path.display());
         }
+
+        if self.hex {
+            println!("{}", hex::encode_prefixed(&artifact.public_output));
+            println!(
+                "{}",
+                hex::encode_prefixed(artifact.proof.unwrap_or_default())
+            );
+        } else if self.output.is_none() {
+            let json = artifact.encode(ArtifactFormat::Json)?;
+            println!("{}", String::from_utf8(json)?);
+        }
+
         Ok(())
     }
 }
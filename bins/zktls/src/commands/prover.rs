@@ -0,0 +1,299 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use clap::Args;
+use zktls_core::{AggregationCommitment, ProofMode, ProofOutput, ZkAggregator, ZkProver};
+use zktls_program_core::GuestInput;
+
+use crate::error::CliError;
+
+use super::{program::ProgramArgs, types::Prover};
+
+/// Which prover backend generates the proofs, and how.
+#[derive(Args)]
+pub struct ProverArgs {
+    /// Use mock mode
+    #[arg(long, group = "proverMode", default_value_t = false)]
+    pub mock: bool,
+
+    /// Use local mode
+    #[arg(long, group = "proverMode", default_value_t = false)]
+    pub local: bool,
+
+    /// Use cuda mode
+    #[cfg(feature = "_cuda")]
+    #[arg(long, group = "proverMode", default_value_t = false)]
+    pub cuda: bool,
+
+    /// Use network mode
+    #[arg(long, group = "proverMode", default_value_t = false)]
+    pub network: bool,
+
+    /// Prover backend to use
+    #[arg(short, long, value_enum)]
+    pub prover: Prover,
+
+    /// Moongate server to use
+    #[arg(short, long, env = "SP1_MOONGATE_SERVER")]
+    pub sp1_moongate_server: Option<String>,
+
+    #[command(flatten)]
+    pub program: ProgramArgs,
+
+    /// Give up on a proof after this number of seconds
+    #[arg(long)]
+    pub deadline: Option<u64>,
+}
+
+impl ProverArgs {
+    pub async fn load_program(&self) -> Result<Vec<u8>> {
+        self.program.load(self.prover.program_type()).await
+    }
+
+    pub async fn prove(
+        &self,
+        input: GuestInput,
+        program: &[u8],
+        mode: ProofMode,
+    ) -> Result<ProofOutput> {
+        Ok(self
+            .prove_with_backend(input, program, mode)
+            .await
+            .map_err(CliError::prover)?)
+    }
+
+    /// Prove `proofs`, compressed proofs of `program`, with one proof of `aggregation_program`.
+    pub async fn aggregate(
+        &self,
+        proofs: Vec<ProofOutput>,
+        program: &[u8],
+        aggregation_program: &[u8],
+        commitment: AggregationCommitment,
+        mode: ProofMode,
+    ) -> Result<ProofOutput> {
+        let output = match self.prover {
+            #[cfg(feature = "r0-backend")]
+            Prover::R0 => {
+                self.r0_prover()
+                    .aggregate(proofs, program, aggregation_program, commitment, mode)
+                    .await
+            }
+            #[cfg(feature = "sp1-backend")]
+            Prover::Sp1 => {
+                self.sp1_prover()
+                    .aggregate(proofs, program, aggregation_program, commitment, mode)
+                    .await
+            }
+        };
+
+        Ok(output.map_err(CliError::prover)?)
+    }
+
+    async fn prove_with_backend(
+        &self,
+        input: GuestInput,
+        program: &[u8],
+        mode: ProofMode,
+    ) -> Result<ProofOutput> {
+        match self.prover {
+            #[cfg(feature = "r0-backend")]
+            Prover::R0 => self.r0_prover().prove(input, program, mode).await,
+            #[cfg(feature = "sp1-backend")]
+            Prover::Sp1 => self.sp1_prover().prove(input, program, mode).await,
+        }
+    }
+
+    #[cfg(feature = "r0-backend")]
+    fn r0_prover(&self) -> zktls_guest_prover_r0::Risc0GuestProver {
+        let mut guest = zktls_guest_prover_r0::Risc0GuestProver::default();
+
+        if let Some(deadline) = self.deadline {
+            guest = guest.deadline(Duration::from_secs(deadline));
+        }
+
+        if self.mock {
+            guest = guest.mock();
+        }
+
+        if self.local {
+            guest = guest.local();
+        }
+
+        #[cfg(feature = "r0-backend-cuda")]
+        if self.cuda {
+            guest = guest.cuda();
+        }
+
+        if self.network {
+            guest = guest.network();
+        }
+
+        guest
+    }
+
+    #[cfg(feature = "sp1-backend")]
+    fn sp1_prover(&self) -> zktls_guest_prover_sp1::SP1GuestProver {
+        let mut guest =
+            zktls_guest_prover_sp1::SP1GuestProver::new(self.sp1_moongate_server.clone());
+        if let Some(deadline) = self.deadline {
+            guest = guest.deadline(Duration::from_secs(deadline));
+        }
+        if self.mock {
+            guest = guest.mock();
+        }
+        if self.local {
+            guest = guest.local();
+        }
+        #[cfg(feature = "sp1-backend-cuda")]
+        if self.cuda {
+            guest = guest.cuda();
+        }
+        if self.network {
+            guest = guest.network();
+        }
+        guest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{keccak256, B256};
+    use clap::Parser;
+    use zktls_core::{
+        ArtifactFormat, Backend, ExecutionOutput, ProofArtifact, ProofMetadata, ProofSystem,
+        ZkExecutor,
+    };
+    use zktls_input_builder::{TLSInputBuilderConfig, TemplatedRequest};
+    use zktls_program_core::Request;
+    use zktls_tls_fixture::FixtureServer;
+
+    use super::*;
+    use crate::commands::input::{build_request_input, request_hash};
+
+    const RESPONSE: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 16\r\n\r\n{\"price\":\"1.25\"}";
+
+    #[derive(Parser)]
+    struct TestCli {
+        #[command(flatten)]
+        prover: ProverArgs,
+    }
+
+    /// Prover without a zkVM: the "program" commits the Keccak-256 of itself
+    /// and of the JSON input, and its proofs are mock proofs.
+    struct MockProver;
+
+    fn mock_output(input: &GuestInput, program: &[u8]) -> Result<Vec<u8>> {
+        let input = serde_json::to_vec(input)?;
+        Ok([keccak256(program), keccak256(input)].concat())
+    }
+
+    impl ZkExecutor for MockProver {
+        async fn execute(&mut self, input: GuestInput, program: &[u8]) -> Result<ExecutionOutput> {
+            Ok(ExecutionOutput {
+                backend: Backend::Sp1,
+                public_output: mock_output(&input, program)?,
+                total_cycles: 0,
+                user_cycles: None,
+                segments: 1,
+                cycle_tracker: Default::default(),
+            })
+        }
+    }
+
+    impl ZkProver for MockProver {
+        async fn prove(
+            &mut self,
+            input: GuestInput,
+            program: &[u8],
+            _mode: ProofMode,
+        ) -> Result<ProofOutput> {
+            Ok(ProofOutput {
+                public_output: mock_output(&input, program)?,
+                proof: None,
+                proof_system: ProofSystem::Mock,
+                metadata: ProofMetadata {
+                    backend: Backend::Sp1,
+                    program: keccak256(program),
+                    proving_time: Duration::ZERO,
+                },
+            })
+        }
+    }
+
+    /// Serve a response with the fixture server, build the input from it, prove
+    /// it in mock mode and verify the proof.
+    async fn prove_fixture<P: ZkProver + ZkExecutor>(
+        prover: &mut P,
+        program: &[u8],
+    ) -> ProofArtifact {
+        let server = FixtureServer::start([RESPONSE.to_vec()]).unwrap();
+        let request: Request =
+            serde_json::from_str(include_str!("../../../../testdata/input.json")).unwrap();
+        let request = server.request(
+            request,
+            b"GET /price HTTP/1.1\r\nHost: localhost\r\nConnection: Close\r\n\r\n",
+        );
+
+        let config = TLSInputBuilderConfig::default().add_root(server.ca_certificate());
+        let request: TemplatedRequest = request.into();
+        let built = build_request_input(config, request.clone()).await.unwrap();
+        let input = built.input;
+        assert_eq!(input.response.response, RESPONSE);
+        assert!(built.trusted_root.is_some());
+
+        let output = prover
+            .prove(input.clone(), program, ProofMode::Groth16)
+            .await
+            .unwrap();
+        assert!(output.is_mock());
+
+        let mut artifact = ProofArtifact::new(output, request_hash(&request).unwrap());
+        artifact.trusted_root = built.trusted_root;
+        let artifact =
+            ProofArtifact::decode(&artifact.encode(ArtifactFormat::Json).unwrap()).unwrap();
+
+        // A mock proof has no proof bytes, it is verified by checking that the
+        // program commits the same output when executed again.
+        assert!(artifact.proof.is_none());
+        assert_eq!(artifact.trusted_root, built.trusted_root);
+        assert_eq!(
+            artifact.public_output,
+            prover.execute(input, program).await.unwrap().public_output
+        );
+
+        artifact
+    }
+
+    #[tokio::test]
+    async fn test_prove_fixture() {
+        let program = b"program";
+        let artifact = prove_fixture(&mut MockProver, program).await;
+
+        assert_eq!(artifact.program, keccak256(program));
+        assert_ne!(artifact.request_hash, B256::ZERO);
+    }
+
+    #[cfg(feature = "sp1-backend")]
+    #[tokio::test]
+    #[ignore = "needs the released SP1 program and toolchain"]
+    async fn test_prove_fixture_sp1() {
+        let args =
+            TestCli::parse_from(["zktls", "--mock", "--allow-unpinned", "--prover", "sp1"]).prover;
+        let program = args.load_program().await.unwrap();
+
+        let artifact = prove_fixture(&mut args.sp1_prover(), &program).await;
+        assert_eq!(artifact.backend, Backend::Sp1);
+    }
+
+    #[cfg(feature = "r0-backend")]
+    #[tokio::test]
+    #[ignore = "needs the released RISC0 program and toolchain"]
+    async fn test_prove_fixture_r0() {
+        let args =
+            TestCli::parse_from(["zktls", "--mock", "--allow-unpinned", "--prover", "r0"]).prover;
+        let program = args.load_program().await.unwrap();
+
+        let artifact = prove_fixture(&mut args.r0_prover(), &program).await;
+        assert_eq!(artifact.backend, Backend::R0);
+    }
+}
@@ -0,0 +1,97 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use zktls_input_builder::RequestBuilder;
+use zktls_program_core::Request;
+
+use crate::error::CliError;
+
+#[derive(Args)]
+pub struct RequestArgs {
+    #[command(subcommand)]
+    pub command: RequestCommands,
+}
+
+#[derive(Subcommand)]
+pub enum RequestCommands {
+    /// Create a request file from an URL, method, headers and body
+    New(RequestNewArgs),
+}
+
+#[derive(Args)]
+pub struct RequestNewArgs {
+    /// URL to request, only https is supported
+    pub url: String,
+
+    /// HTTP method
+    #[arg(short = 'X', long, default_value = "GET")]
+    pub method: String,
+
+    /// Header to send, as `Name: value`
+    #[arg(short = 'H', long = "header")]
+    pub headers: Vec<String>,
+
+    /// Body to send
+    #[arg(short, long, conflicts_with = "data_file")]
+    pub data: Option<String>,
+
+    /// File with the body to send
+    #[arg(long)]
+    pub data_file: Option<PathBuf>,
+
+    /// Request file to take the target and origin from
+    #[arg(short, long)]
+    pub base: Option<PathBuf>,
+
+    /// Path to write the request file, stdout if not set
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+impl RequestArgs {
+    pub fn execute(&self) -> Result<()> {
+        match &self.command {
+            RequestCommands::New(args) => Ok(args.execute().map_err(CliError::Request)?),
+        }
+    }
+}
+
+impl RequestNewArgs {
+    pub fn execute(&self) -> Result<()> {
+        let mut builder = RequestBuilder::new(&self.url)?.method(&self.method);
+
+        for header in &self.headers {
+            let (name, value) = header
+                .split_once(':')
+                .ok_or_else(|| anyhow::anyhow!("header must be `Name: value`: {}", header))?;
+
+            builder = builder.header(name.trim(), value.trim());
+        }
+
+        if let Some(data) = &self.data {
+            builder = builder.body(data.as_bytes());
+        }
+
+        if let Some(path) = &self.data_file {
+            builder = builder.body(fs::read(path)?);
+        }
+
+        let request = match &self.base {
+            Some(path) => {
+                let base: Request = serde_json::from_str(&fs::read_to_string(path)?)?;
+                builder.build_with(base)?
+            }
+            None => builder.build()?,
+        };
+
+        let json = serde_json::to_string_pretty(&request)?;
+
+        match &self.output {
+            Some(path) => fs::write(path, json)?,
+            None => println!("{}", json),
+        }
+
+        Ok(())
+    }
+}
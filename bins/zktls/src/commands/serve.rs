@@ -0,0 +1,314 @@
+use std::{net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use clap::Args;
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::{net::TcpListener, sync::Notify};
+use zktls_core::{abandoned_provers, running_provers, wait_for_provers, ProofArtifact, ProofMode};
+use zktls_input_builder::TemplatedRequest;
+use zktls_job_queue::{Job, JobId, JobQueue, JobState};
+
+use crate::program::home_dir;
+
+use super::{
+    input::{build_request_input, request_hash, RootArgs},
+    prover::ProverArgs,
+};
+
+/// How often idle workers look for jobs whose retry is due.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Args)]
+pub struct ServeArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    pub listen: SocketAddr,
+
+    /// Number of jobs proved at the same time
+    #[arg(long, default_value_t = 1)]
+    pub concurrency: usize,
+
+    /// Directory of the job queue, `~/.local/zktlsd` by default
+    #[arg(long)]
+    pub data_dir: Option<PathBuf>,
+
+    /// Attempts of a job before a retryable failure becomes fatal
+    #[arg(long, default_value_t = 3)]
+    pub max_attempts: u32,
+
+    /// Seconds before the retry of a job, multiplied by its attempts
+    #[arg(long, default_value_t = 30)]
+    pub retry_backoff: u64,
+
+    #[command(flatten)]
+    pub roots: RootArgs,
+
+    #[command(flatten)]
+    pub prover: ProverArgs,
+
+    /// Kind of proof of the jobs which don't set one
+    #[arg(long, default_value_t = ProofMode::Groth16)]
+    pub proof_mode: ProofMode,
+}
+
+#[derive(Clone)]
+struct AppState {
+    queue: Arc<JobQueue>,
+    notify: Arc<Notify>,
+    proof_mode: ProofMode,
+    concurrency: usize,
+}
+
+#[derive(Deserialize)]
+struct SubmitJob {
+    request: TemplatedRequest,
+    #[serde(default)]
+    proof_mode: Option<ProofMode>,
+}
+
+#[derive(Serialize)]
+struct JobInfo {
+    id: JobId,
+    state: JobState,
+    proof_mode: ProofMode,
+    attempts: u32,
+    created_at: u64,
+    updated_at: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl From<&Job> for JobInfo {
+    fn from(job: &Job) -> Self {
+        Self {
+            id: job.id,
+            state: job.state,
+            proof_mode: job.proof_mode,
+            attempts: job.attempts,
+            created_at: job.created_at,
+            updated_at: job.updated_at,
+            error: job.error.clone(),
+        }
+    }
+}
+
+struct ApiError(StatusCode, String);
+
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        Self(StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", e))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+/// Run a call of the job queue on the blocking pool, redb waits for the disk
+/// and syncs every write transaction.
+async fn with_queue<T, F>(queue: &Arc<JobQueue>, f: F) -> Result<T>
+where
+    F: FnOnce(&JobQueue) -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    let queue = queue.clone();
+
+    tokio::task::spawn_blocking(move || f(&queue)).await?
+}
+
+impl ServeArgs {
+    pub async fn execute(&self) -> Result<()> {
+        let data_dir = match &self.data_dir {
+            Some(dir) => dir.clone(),
+            None => home_dir()?,
+        };
+        tokio::fs::create_dir_all(&data_dir).await?;
+
+        // Jobs left running by a previous process are pending again.
+        let queue = Arc::new(
+            JobQueue::open(data_dir.join("jobs.redb"))?
+                .max_attempts(self.max_attempts)
+                .backoff(Duration::from_secs(self.retry_backoff)),
+        );
+        let notify = Arc::new(Notify::new());
+
+        // Load the program once, before accepting jobs.
+        let program = self.prover.load_program().await?;
+
+        let state = AppState {
+            queue: queue.clone(),
+            notify: notify.clone(),
+            proof_mode: self.proof_mode,
+            concurrency: self.concurrency.max(1),
+        };
+        let app = Router::new()
+            .route("/jobs", post(submit_job))
+            .route("/jobs/:id", get(job_status))
+            .route("/jobs/:id/proof", get(job_proof))
+            .route("/provers", get(provers))
+            .with_state(state);
+
+        let listener = TcpListener::bind(self.listen).await?;
+        log::info!("listening on {}", listener.local_addr()?);
+
+        // Jobs run in this task, proofs themselves run on their own threads.
+        let workers =
+            join_all((0..self.concurrency.max(1)).map(|_| self.worker(&queue, &notify, &program)));
+
+        let server = axum::serve(listener, app).with_graceful_shutdown(async {
+            tokio::signal::ctrl_c().await.ok();
+        });
+
+        // Jobs interrupted by the shutdown are resumed on the next start.
+        tokio::select! {
+            res = server => res?,
+            _ = workers => {}
+        }
+
+        log::info!("shutting down");
+
+        Ok(())
+    }
+
+    async fn worker(&self, queue: &Arc<JobQueue>, notify: &Notify, program: &[u8]) {
+        loop {
+            match with_queue(queue, |queue| queue.claim()).await {
+                Ok(Some(job)) => self.run_job(queue, program, job).await,
+                Ok(None) => {
+                    tokio::select! {
+                        _ = notify.notified() => {}
+                        _ = tokio::time::sleep(POLL_INTERVAL) => {}
+                    }
+                }
+                Err(e) => {
+                    log::error!("failed to claim a job: {:#}", e);
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+            }
+        }
+    }
+
+    async fn run_job(&self, queue: &Arc<JobQueue>, program: &[u8], job: Job) {
+        log::info!("proving job {}, attempt {}", job.id, job.attempts);
+
+        let id = job.id;
+        let updated = match self.prove(queue, &job, program).await {
+            Ok(artifact) => with_queue(queue, move |queue| queue.succeed(id, artifact)).await,
+            Err(e) => with_queue(queue, move |queue| queue.fail(id, &e)).await,
+        };
+
+        match updated {
+            Ok(job) => match job.state {
+                JobState::Succeeded => log::info!("job {} succeeded", job.id),
+                JobState::Pending => log::warn!(
+                    "job {} failed, retrying: {}",
+                    job.id,
+                    job.error.unwrap_or_default()
+                ),
+                _ => log::warn!("job {} failed: {}", job.id, job.error.unwrap_or_default()),
+            },
+            Err(e) => log::error!("failed to update job {}: {:#}", job.id, e),
+        }
+    }
+
+    async fn prove(
+        &self,
+        queue: &Arc<JobQueue>,
+        job: &Job,
+        program: &[u8],
+    ) -> Result<ProofArtifact> {
+        let id = job.id;
+        let request_hash = request_hash(&job.request)?;
+
+        // Retries reuse the input of the previous attempt, instead of calling the server again.
+        let (input, trusted_root) = match with_queue(queue, move |queue| queue.input(id)).await? {
+            Some(input) => (input, job.trusted_root),
+            None => {
+                let built = build_request_input(self.roots.config(), job.request.clone()).await?;
+                with_queue(queue, move |queue| {
+                    queue
+                        .set_input(id, &built.input, built.trusted_root)
+                        .map(|job| (built.input, job.trusted_root))
+                })
+                .await?
+            }
+        };
+
+        // A proof abandoned after its deadline keeps running, don't start more
+        // than `concurrency` proofs until it exits.
+        wait_for_provers(self.concurrency.max(1)).await;
+        let output = self.prover.prove(input, program, job.proof_mode).await?;
+
+        let mut artifact = ProofArtifact::new(output, request_hash);
+        artifact.trusted_root = trusted_root;
+
+        Ok(artifact)
+    }
+}
+
+async fn submit_job(
+    State(state): State<AppState>,
+    Json(body): Json<SubmitJob>,
+) -> Result<(StatusCode, Json<JobInfo>), ApiError> {
+    let proof_mode = body.proof_mode.unwrap_or(state.proof_mode);
+    let job = with_queue(&state.queue, move |queue| {
+        queue.push(body.request, proof_mode)
+    })
+    .await?;
+    state.notify.notify_one();
+
+    log::info!("queued job {}", job.id);
+
+    Ok((StatusCode::ACCEPTED, Json(JobInfo::from(&job))))
+}
+
+async fn find_job(state: &AppState, id: JobId) -> Result<Job, ApiError> {
+    with_queue(&state.queue, move |queue| queue.get(id))
+        .await?
+        .ok_or_else(|| ApiError(StatusCode::NOT_FOUND, format!("no job {}", id)))
+}
+
+async fn job_status(
+    State(state): State<AppState>,
+    Path(id): Path<JobId>,
+) -> Result<Json<JobInfo>, ApiError> {
+    let job = find_job(&state, id).await?;
+
+    Ok(Json(JobInfo::from(&job)))
+}
+
+async fn job_proof(
+    State(state): State<AppState>,
+    Path(id): Path<JobId>,
+) -> Result<Json<ProofArtifact>, ApiError> {
+    let job = find_job(&state, id).await?;
+
+    match job.artifact {
+        Some(artifact) => Ok(Json(artifact)),
+        None => Err(ApiError(
+            StatusCode::CONFLICT,
+            format!("job {} is {:?}", id, job.state),
+        )),
+    }
+}
+
+/// Proof slots in use, including the ones held by proofs abandoned after their
+/// deadline, which can't be interrupted.
+async fn provers(State(state): State<AppState>) -> Json<serde_json::Value> {
+    Json(json!({
+        "concurrency": state.concurrency,
+        "running": running_provers(),
+        "abandoned": abandoned_provers(),
+    }))
+}
@@ -16,3 +16,66 @@ pub enum Prover {
     #[cfg(feature = "r0-backend")]
     R0,
 }
+
+impl Prover {
+    pub fn backend(&self) -> zktls_core::Backend {
+        match self {
+            #[cfg(feature = "sp1-backend")]
+            Prover::Sp1 => zktls_core::Backend::Sp1,
+            #[cfg(feature = "r0-backend")]
+            Prover::R0 => zktls_core::Backend::R0,
+        }
+    }
+
+    /// Type of the program in the registry.
+    pub fn program_type(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "sp1-backend")]
+            Prover::Sp1 => "sp1",
+            #[cfg(feature = "r0-backend")]
+            Prover::R0 => "r0",
+        }
+    }
+
+    /// Type of the aggregation program in the registry.
+    pub fn aggregation_program_type(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "sp1-backend")]
+            Prover::Sp1 => "aggregate-sp1",
+            #[cfg(feature = "r0-backend")]
+            Prover::R0 => "aggregate-r0",
+        }
+    }
+
+    /// Type of the zkTLS program, or of the aggregation program if `aggregation` is set.
+    pub fn registry_type(&self, aggregation: bool) -> &'static str {
+        if aggregation {
+            self.aggregation_program_type()
+        } else {
+            self.program_type()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::program::{pinned_digest, PROGRAM_VERSION};
+
+    use super::*;
+
+    #[test]
+    #[ignore = "the digests of the v0.2.5 programs are not pinned yet"]
+    fn test_selectable_programs_pinned() {
+        for prover in Prover::value_variants() {
+            for aggregation in [false, true] {
+                let ty = prover.registry_type(aggregation);
+                assert!(
+                    pinned_digest(ty, PROGRAM_VERSION).is_some(),
+                    "release {} of program {} has no pinned digest",
+                    PROGRAM_VERSION,
+                    ty
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::path::PathBuf;
+
+use alloy_primitives::B256;
+use anyhow::{anyhow, Result};
+use clap::Args;
+use zktls_core::{Backend, ProofArtifact, ProofMode};
+
+use crate::{error::CliError, utils};
+
+use super::{program::ProgramArgs, types::Prover};
+
+#[derive(Args)]
+pub struct VerifyArgs {
+    /// Path to a proof artifact written by `prove --output`
+    #[arg(short, long, conflicts_with_all = ["proof", "public_output", "prover"])]
+    pub artifact: Option<PathBuf>,
+
+    /// Path to the proof, as hex or raw bytes
+    #[arg(long, required_unless_present = "artifact")]
+    pub proof: Option<PathBuf>,
+
+    /// Path to the public output of the proof, as hex or raw bytes
+    #[arg(long, required_unless_present = "artifact")]
+    pub public_output: Option<PathBuf>,
+
+    /// Prover backend which generated the proof
+    #[arg(short, long, value_enum, required_unless_present = "artifact")]
+    pub prover: Option<Prover>,
+
+    /// Kind of proof: core, compressed, groth16 or plonk
+    #[arg(long, default_value_t = ProofMode::Groth16, conflicts_with = "artifact")]
+    pub proof_mode: ProofMode,
+
+    #[command(flatten)]
+    pub program: ProgramArgs,
+}
+
+impl VerifyArgs {
+    pub async fn execute(&self) -> Result<()> {
+        let (backend, artifact_program, output, proof, proof_system) = match &self.artifact {
+            Some(path) => {
+                let artifact = ProofArtifact::load(path)?;
+                let proof = artifact.proof.ok_or_else(|| {
+                    anyhow!("artifact has no proof, it was generated in mock mode")
+                })?;
+                (
+                    Some(artifact.backend),
+                    Some(artifact.program),
+                    artifact.public_output.to_vec(),
+                    proof.to_vec(),
+                    artifact.proof_system,
+                )
+            }
+            None => {
+                let backend = self.prover.as_ref().map(Prover::backend);
+                let proof =
+                    utils::read_bytes(self.proof.as_ref().expect("required by clap")).await?;
+                let output =
+                    utils::read_bytes(self.public_output.as_ref().expect("required by clap"))
+                        .await?;
+                (backend, None, output, proof, self.proof_mode.into())
+            }
+        };
+
+        match backend {
+            #[cfg(feature = "sp1-backend")]
+            Some(Backend::Sp1) => {
+                let program = self.program.load("sp1").await?;
+                let vkey = zktls_guest_prover_sp1::program_vkey(&program);
+                check_program(artifact_program, vkey.parse()?)?;
+                zktls_guest_prover_sp1::verify(&program, &output, &proof, proof_system)
+                    .map_err(CliError::InvalidProof)?;
+            }
+            #[cfg(feature = "r0-backend")]
+            Some(Backend::R0) => {
+                let program = self.program.load("r0").await?;
+                let image_id =
+                    zktls_guest_prover_r0::image_id(&program).map_err(CliError::Program)?;
+                check_program(artifact_program, image_id.into())?;
+                zktls_guest_prover_r0::verify(&program, &output, &proof, proof_system)
+                    .map_err(CliError::InvalidProof)?;
+            }
+            _ => return Err(anyhow!("backend of the proof is not enabled in this build")),
+        }
+
+        println!("Proof is valid");
+
+        Ok(())
+    }
+}
+
+/// Fail before verifying if the artifact was proved by another program.
+fn check_program(artifact: Option<B256>, loaded: B256) -> Result<(), CliError> {
+    match artifact {
+        Some(artifact) if artifact != loaded => Err(CliError::ProgramMismatch { artifact, loaded }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_program() {
+        let loaded = B256::repeat_byte(1);
+
+        assert!(check_program(None, loaded).is_ok());
+        assert!(check_program(Some(loaded), loaded).is_ok());
+
+        let err = check_program(Some(B256::repeat_byte(2)), loaded).unwrap_err();
+        assert_eq!(err.kind(), "program_mismatch");
+        assert_eq!(err.exit_code(), 11);
+    }
+}
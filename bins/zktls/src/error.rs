@@ -0,0 +1,253 @@
+use std::{
+    any::Any,
+    io::{self, Write},
+    panic::PanicHookInfo,
+    process::ExitCode,
+    sync::{Mutex, PoisonError},
+    thread,
+};
+
+use alloy_primitives::B256;
+use serde_json::{json, Value};
+use zktls_core::{panic_message, ProveError};
+use zktls_input_builder::{TemplateFailure, TlsCallError};
+
+/// Error of a command, its kind decides the exit code of the process.
+///
+/// Exit code 2 is left to clap, for invalid arguments.
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    /// The request file is missing or invalid.
+    #[error("invalid request")]
+    Request(#[source] anyhow::Error),
+    /// The TLS call to the server failed.
+    #[error("TLS call failed")]
+    Tls(#[source] anyhow::Error),
+    /// A response template didn't match the response of the server.
+    #[error("invalid response")]
+    Template(#[source] anyhow::Error),
+    #[error("failed to load the program")]
+    Program(#[source] anyhow::Error),
+    #[error("guest rejected the input: {0}")]
+    GuestRejected(String),
+    #[error("prover failed")]
+    Prover(#[source] anyhow::Error),
+    #[error("invalid proof")]
+    InvalidProof(#[source] anyhow::Error),
+    /// The artifact was proved by another program than the loaded one.
+    #[error("proof is for program {artifact}, but the loaded program is {loaded}")]
+    ProgramMismatch { artifact: B256, loaded: B256 },
+    /// Some requests of a batch failed, the others were proved.
+    #[error("{failed} of {total} requests failed")]
+    BatchFailed { failed: usize, total: usize },
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl CliError {
+    /// Error of building the guest input from the response of the server.
+    pub fn input(e: anyhow::Error) -> Self {
+        if e.chain().any(|cause| cause.is::<TemplateFailure>()) {
+            Self::Template(e)
+        } else {
+            Self::Tls(e)
+        }
+    }
+
+    /// Error of proving or executing the program.
+    pub fn prover(e: anyhow::Error) -> Self {
+        for cause in e.chain() {
+            if let Some(ProveError::GuestRejected(message)) = cause.downcast_ref() {
+                return Self::GuestRejected(message.clone());
+            }
+        }
+
+        Self::Prover(e)
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Request(_) => "request",
+            Self::Tls(_) => "tls",
+            Self::Template(_) => "template",
+            Self::Program(_) => "program",
+            Self::GuestRejected(_) => "guest_rejected",
+            Self::Prover(_) => "prover",
+            Self::InvalidProof(_) => "invalid_proof",
+            Self::ProgramMismatch { .. } => "program_mismatch",
+            Self::BatchFailed { .. } => "batch_failed",
+            Self::Other(_) => "other",
+        }
+    }
+
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::Other(_) => 1,
+            Self::Request(_) => 3,
+            Self::Tls(_) => 4,
+            Self::Template(_) => 5,
+            Self::Program(_) => 6,
+            Self::GuestRejected(_) => 7,
+            Self::Prover(_) => 8,
+            Self::InvalidProof(_) => 9,
+            Self::BatchFailed { .. } => 10,
+            Self::ProgramMismatch { .. } => 11,
+        }
+    }
+
+    /// Message of the error and of its causes.
+    pub fn message(&self) -> String {
+        let mut message = self.to_string();
+
+        let mut source = std::error::Error::source(self);
+        while let Some(cause) = source {
+            message.push_str(": ");
+            message.push_str(&cause.to_string());
+            source = cause.source();
+        }
+
+        message
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "error": {
+                "kind": self.kind(),
+                "code": self.exit_code(),
+                "message": self.message(),
+            }
+        })
+    }
+
+    /// Print the error to stderr, as a JSON object if `json` is set.
+    pub fn report(&self, json: bool) {
+        let _ = self.write_report(json, &mut io::stderr().lock());
+    }
+
+    fn write_report(&self, json: bool, out: &mut impl Write) -> io::Result<()> {
+        if json {
+            writeln!(out, "{}", self.to_json())
+        } else {
+            writeln!(out, "Error: {}", self.message())
+        }
+    }
+}
+
+impl From<anyhow::Error> for CliError {
+    /// Keep the kind given by the command, or guess it from the error chain.
+    fn from(e: anyhow::Error) -> Self {
+        let e = match e.downcast::<CliError>() {
+            Ok(e) => return e,
+            Err(e) => e,
+        };
+
+        for cause in e.chain() {
+            if cause.is::<TemplateFailure>() {
+                return Self::Template(e);
+            }
+            if cause.is::<TlsCallError>() {
+                return Self::Tls(e);
+            }
+            if cause.is::<ProveError>() {
+                return Self::prover(e);
+            }
+        }
+
+        Self::Other(e)
+    }
+}
+
+/// Location of the last panic of the main thread, see [`report_panic`].
+static MAIN_PANIC_LOCATION: Mutex<Option<String>> = Mutex::new(None);
+
+/// Keep panics quiet, so `--json-errors` prints a single JSON object.
+///
+/// Panics caught by the provers are reported as prover errors, and a panic
+/// ending the command is reported by [`report_panic`] with the location
+/// recorded here.
+pub fn set_json_panic_hook() {
+    std::panic::set_hook(Box::new(|info: &PanicHookInfo| {
+        log::debug!("{}", info);
+
+        if thread::current().name() == Some("main") {
+            *MAIN_PANIC_LOCATION
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner) = info.location().map(|l| l.to_string());
+        }
+    }));
+}
+
+/// Report a panic of the command as a JSON error, with the exit code of a panic.
+pub fn report_panic(payload: Box<dyn Any + Send>) -> ExitCode {
+    let location = MAIN_PANIC_LOCATION
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take();
+
+    eprintln!(
+        "{}",
+        json!({
+            "error": {
+                "kind": "panic",
+                "code": 101,
+                "message": panic_message(payload.as_ref()),
+                "location": location,
+            }
+        })
+    );
+
+    ExitCode::from(101)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_error_kinds() {
+        let e = anyhow::Error::from(CliError::Request(anyhow::anyhow!("missing file")));
+        assert_eq!(CliError::from(e).exit_code(), 3);
+
+        let e = anyhow::Error::from(ProveError::GuestRejected("exit code 1".into()));
+        let e = CliError::from(e.context("executing"));
+        assert_eq!(e.kind(), "guest_rejected");
+        assert_eq!(e.to_string(), "guest rejected the input: exit code 1");
+
+        let e = CliError::from(anyhow::Error::from(ProveError::DeadlineExceeded(
+            Duration::from_secs(1),
+        )));
+        assert_eq!(e.exit_code(), 8);
+        assert_eq!(e.message(), "prover failed: proof did not finish within 1s");
+
+        let e = CliError::input(anyhow::Error::from(TlsCallError::Cancelled));
+        assert_eq!(e.kind(), "tls");
+
+        assert_eq!(CliError::from(anyhow::anyhow!("boom")).exit_code(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_caught_prover_panic() {
+        set_json_panic_hook();
+        let e = zktls_core::spawn_prover(None, || -> anyhow::Result<()> { panic!("prover bug") })
+            .await
+            .unwrap_err();
+        let _ = std::panic::take_hook();
+
+        let mut out = Vec::new();
+        CliError::from(e).write_report(true, &mut out).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        let reports: Vec<Value> = out
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0]["error"]["code"], 8);
+        assert_eq!(
+            reports[0]["error"]["message"],
+            "prover failed: prover panicked: prover bug"
+        );
+    }
+}
@@ -1,14 +1,32 @@
+use std::{panic::AssertUnwindSafe, process::ExitCode};
+
 use clap::{Parser, Subcommand};
+use futures_util::FutureExt;
 
 mod commands;
+mod error;
+mod program;
 mod utils;
-use commands::{ExportVerifierArgs, ProveArgs};
+use commands::{
+    AggregateArgs, ExecuteArgs, ExportVerifierArgs, ProgramCommandArgs, ProveArgs, RequestArgs,
+    ServeArgs, VerifyArgs,
+};
+use error::CliError;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Print errors to stderr as JSON objects with their kind and exit code
+    #[arg(
+        long,
+        global = true,
+        env = "ZKTLS_JSON_ERRORS",
+        default_value_t = false
+    )]
+    json_errors: bool,
 }
 
 #[derive(Subcommand)]
@@ -16,18 +34,95 @@ enum Commands {
     /// Generate a proof using the input request file
     Prove(ProveArgs),
 
+    /// Execute the program on the input without proving, and report its cycles
+    Execute(ExecuteArgs),
+
+    /// Aggregate compressed proofs into a single proof
+    Aggregate(AggregateArgs),
+
+    /// Verify a proof offline
+    Verify(VerifyArgs),
+
     /// Export verifier for the target chain
     ExportVerifier(ExportVerifierArgs),
+
+    /// Create and inspect request files
+    Request(RequestArgs),
+
+    /// Manage the programs of the registry
+    Program(ProgramCommandArgs),
+
+    /// Run a prover daemon serving an HTTP/JSON API
+    Serve(ServeArgs),
 }
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
+async fn main() -> ExitCode {
     env_logger::init();
 
     let cli = Cli::parse();
 
-    match &cli.command {
-        Commands::Prove(args) => args.execute().await,
-        Commands::ExportVerifier(args) => args.execute(),
+    if cli.json_errors {
+        error::set_json_panic_hook();
+    }
+
+    let command = async {
+        match &cli.command {
+            Commands::Prove(args) => args.execute().await,
+            Commands::Execute(args) => args.execute().await,
+            Commands::Aggregate(args) => args.execute().await,
+            Commands::Verify(args) => args.execute().await,
+            Commands::ExportVerifier(args) => args.execute().await,
+            Commands::Request(args) => args.execute(),
+            Commands::Program(args) => args.execute().await,
+            Commands::Serve(args) => args.execute().await,
+        }
+    };
+
+    let result = if cli.json_errors {
+        match AssertUnwindSafe(command).catch_unwind().await {
+            Ok(result) => result,
+            Err(panic) => return error::report_panic(panic),
+        }
+    } else {
+        command.await
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            let e = CliError::from(e);
+            e.report(cli.json_errors);
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn test_cli() {
+        Cli::command().debug_assert();
+
+        assert!(Cli::try_parse_from(["zktls", "prove", "-t", "evm", "-p", "sp1"]).is_err());
+        assert!(Cli::try_parse_from([
+            "zktls", "prove", "-t", "evm", "-p", "sp1", "-i", "req.json"
+        ])
+        .is_ok());
+        assert!(Cli::try_parse_from([
+            "zktls",
+            "prove",
+            "-t",
+            "evm",
+            "-p",
+            "sp1",
+            "--batch",
+            "reqs.jsonl"
+        ])
+        .is_ok());
     }
 }
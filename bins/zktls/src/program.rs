@@ -0,0 +1,386 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use alloy_primitives::B256;
+use anyhow::{anyhow, Result};
+use futures_util::StreamExt;
+use sha2::{Digest, Sha256};
+use tokio::{fs, io::AsyncWriteExt};
+
+/// Release of the zkTLS programs used by this binary.
+pub const PROGRAM_VERSION: &str = "v0.2.5";
+
+/// Default mirror of the programs, the GitHub releases.
+pub const DEFAULT_MIRROR: &str = "https://github.com/the3cloud/zkvm-programs/releases/download";
+
+/// SHA-256 digests of the released programs, by program type and release, as
+/// in `("sp1", "v0.2.5", b256!("<sha256 of zktls-sp1>"))`. The aggregation
+/// programs are the `aggregate-sp1` and `aggregate-r0` types.
+///
+/// The registry refuses the releases missing here, unless unpinned programs are
+/// allowed: they are then trusted on first download, their digest is recorded
+/// next to them and checked on every load.
+const PROGRAM_DIGESTS: &[(&str, &str, B256)] = &[];
+
+/// Programs embedded in the binary with the `embedded-programs` feature, for
+/// [`PROGRAM_VERSION`]. They are read from `$ZKTLS_PROGRAM_BUNDLE` at build time.
+const EMBEDDED_PROGRAMS: &[(&str, &[u8])] = &[
+    #[cfg(all(feature = "embedded-programs", feature = "sp1-backend"))]
+    (
+        "sp1",
+        include_bytes!(concat!(env!("ZKTLS_PROGRAM_BUNDLE"), "/zktls-sp1")),
+    ),
+    #[cfg(all(feature = "embedded-programs", feature = "r0-backend"))]
+    (
+        "r0",
+        include_bytes!(concat!(env!("ZKTLS_PROGRAM_BUNDLE"), "/zktls-r0")),
+    ),
+];
+
+/// `~/.local/zktlsd`, where the programs and the daemon data are stored by default.
+pub fn home_dir() -> Result<PathBuf> {
+    let home = env::var("HOME")?;
+
+    Ok(Path::new(&home).join(".local").join("zktlsd"))
+}
+
+pub fn program_digest(program: &[u8]) -> B256 {
+    B256::from_slice(&Sha256::digest(program))
+}
+
+/// Digest pinned for the release of a program type, see [`PROGRAM_DIGESTS`].
+pub fn pinned_digest(ty: &str, version: &str) -> Option<B256> {
+    PROGRAM_DIGESTS
+        .iter()
+        .find(|(t, v, _)| *t == ty && *v == version)
+        .map(|(_, _, digest)| *digest)
+}
+
+fn embedded_program(ty: &str, version: &str) -> Option<&'static [u8]> {
+    if version != PROGRAM_VERSION {
+        return None;
+    }
+
+    EMBEDDED_PROGRAMS
+        .iter()
+        .find(|(t, _)| *t == ty)
+        .map(|(_, program)| *program)
+}
+
+/// Program stored in the registry directory.
+#[derive(Debug, Clone)]
+pub struct ProgramEntry {
+    pub ty: String,
+    pub version: String,
+    pub path: PathBuf,
+    pub digest: B256,
+    /// Whether the digest is the pinned one of the release, `None` if there is no pin.
+    pub pinned: Option<bool>,
+}
+
+fn unpinned_error(ty: &str, version: &str) -> anyhow::Error {
+    anyhow!(
+        "release {} of program {} has no pinned digest, pass --allow-unpinned to trust it anyway",
+        version,
+        ty
+    )
+}
+
+/// Resolves a program type and release to an ELF.
+///
+/// Programs are looked up in the local directory, `<dir>/<version>/zktls-<type>`,
+/// then in the programs embedded in the binary, and finally downloaded from the
+/// mirror, `<mirror>/<version>/zktls-<type>`, into the local directory. All of
+/// them must match the digest pinned in [`PROGRAM_DIGESTS`].
+#[derive(Debug, Clone)]
+pub struct ProgramRegistry {
+    dir: PathBuf,
+    mirror: String,
+    offline: bool,
+    allow_unpinned: bool,
+}
+
+impl ProgramRegistry {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            mirror: DEFAULT_MIRROR.to_string(),
+            offline: false,
+            allow_unpinned: false,
+        }
+    }
+
+    /// Registry in `~/.local/zktlsd`.
+    pub fn from_home() -> Result<Self> {
+        Ok(Self::new(home_dir()?))
+    }
+
+    pub fn mirror(mut self, mirror: impl Into<String>) -> Self {
+        self.mirror = mirror.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Never download programs.
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Trust the programs of releases without a pinned digest on first use.
+    pub fn allow_unpinned(mut self, allow_unpinned: bool) -> Self {
+        self.allow_unpinned = allow_unpinned;
+        self
+    }
+
+    fn program_path(&self, ty: &str, version: &str) -> PathBuf {
+        self.dir.join(version).join(format!("zktls-{}", ty))
+    }
+
+    fn digest_path(&self, ty: &str, version: &str) -> PathBuf {
+        self.dir.join(version).join(format!("zktls-{}.sha256", ty))
+    }
+
+    /// Digest the program must have, `None` for an unpinned program seen for the first time.
+    async fn expected_digest(&self, ty: &str, version: &str) -> Result<Option<B256>> {
+        if let Some(digest) = pinned_digest(ty, version) {
+            return Ok(Some(digest));
+        }
+
+        if !self.allow_unpinned {
+            return Err(unpinned_error(ty, version));
+        }
+
+        let digest_path = self.digest_path(ty, version);
+        if !digest_path.exists() {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            fs::read_to_string(&digest_path).await?.trim().parse()?,
+        ))
+    }
+
+    /// Load the program, downloading it again if its digest doesn't match the expected one.
+    pub async fn get(&self, ty: &str, version: &str) -> Result<Vec<u8>> {
+        let path = self.program_path(ty, version);
+        let expected = self.expected_digest(ty, version).await?;
+
+        if path.exists() {
+            let program = fs::read(&path).await?;
+            let digest = program_digest(&program);
+
+            match expected {
+                Some(expected) if digest == expected => return Ok(program),
+                Some(expected) => log::warn!(
+                    "program {} has sha256 {}, expected {}",
+                    path.display(),
+                    digest,
+                    expected
+                ),
+                None => log::warn!("program {} has no recorded digest", path.display()),
+            }
+        }
+
+        if let Some(program) = embedded_program(ty, version) {
+            let digest = program_digest(program);
+            if expected.is_some_and(|expected| expected != digest) {
+                return Err(anyhow!(
+                    "embedded program {} {} has sha256 {}, expected {}",
+                    ty,
+                    version,
+                    digest,
+                    expected.expect("checked above")
+                ));
+            }
+
+            log::info!("using embedded program {} {}", ty, version);
+            return Ok(program.to_vec());
+        }
+
+        if self.offline {
+            return Err(anyhow!(
+                "program {} {} is not available offline, import it with `zktls program import`",
+                ty,
+                version
+            ));
+        }
+
+        self.fetch(ty, version).await?;
+
+        Ok(fs::read(&path).await?)
+    }
+
+    /// Download the program from the mirror and check its digest.
+    pub async fn fetch(&self, ty: &str, version: &str) -> Result<PathBuf> {
+        let url = format!("{}/{}/zktls-{}", self.mirror, version, ty);
+        let path = self.program_path(ty, version);
+        let expected = self.expected_digest(ty, version).await?;
+
+        fs::create_dir_all(path.parent().expect("program path has a parent")).await?;
+
+        log::info!("downloading program from {}", url);
+        download_program(&url, &path).await?;
+
+        let digest = program_digest(&fs::read(&path).await?);
+        match expected {
+            Some(expected) if digest != expected => {
+                fs::remove_file(&path).await?;
+                return Err(anyhow!(
+                    "downloaded program {} has sha256 {}, expected {}",
+                    url,
+                    digest,
+                    expected
+                ));
+            }
+            Some(_) => {}
+            None => fs::write(self.digest_path(ty, version), digest.to_string()).await?,
+        }
+
+        log::info!(
+            "downloaded program success {} with sha256 {}",
+            path.display(),
+            digest
+        );
+
+        Ok(path)
+    }
+
+    /// Copy a program into the registry and trust its digest.
+    pub async fn import(&self, ty: &str, version: &str, source: &Path) -> Result<ProgramEntry> {
+        let program = fs::read(source).await?;
+        let digest = program_digest(&program);
+
+        let pinned = pinned_digest(ty, version);
+        if pinned.is_none() && !self.allow_unpinned {
+            return Err(unpinned_error(ty, version));
+        }
+        if pinned.is_some_and(|pinned| pinned != digest) {
+            return Err(anyhow!(
+                "program {} has sha256 {}, but release {} is pinned to {}",
+                source.display(),
+                digest,
+                version,
+                pinned.expect("checked above")
+            ));
+        }
+
+        let path = self.program_path(ty, version);
+        fs::create_dir_all(path.parent().expect("program path has a parent")).await?;
+
+        let tmp = path.with_extension("import");
+        fs::write(&tmp, &program).await?;
+        fs::rename(&tmp, &path).await?;
+        fs::write(self.digest_path(ty, version), digest.to_string()).await?;
+
+        Ok(ProgramEntry {
+            ty: ty.to_string(),
+            version: version.to_string(),
+            path,
+            digest,
+            pinned: pinned.map(|_| true),
+        })
+    }
+
+    /// Programs stored in the registry directory.
+    pub async fn list(&self) -> Result<Vec<ProgramEntry>> {
+        let mut entries = Vec::new();
+        if !self.dir.exists() {
+            return Ok(entries);
+        }
+
+        let mut versions = fs::read_dir(&self.dir).await?;
+        while let Some(version) = versions.next_entry().await? {
+            if !version.file_type().await?.is_dir() {
+                continue;
+            }
+            let version_name = version.file_name().to_string_lossy().to_string();
+
+            let mut programs = fs::read_dir(version.path()).await?;
+            while let Some(program) = programs.next_entry().await? {
+                let name = program.file_name().to_string_lossy().to_string();
+                let Some(ty) = name.strip_prefix("zktls-") else {
+                    continue;
+                };
+                if ty.contains('.') {
+                    continue;
+                }
+
+                let digest = program_digest(&fs::read(program.path()).await?);
+                entries.push(ProgramEntry {
+                    ty: ty.to_string(),
+                    version: version_name.clone(),
+                    path: program.path(),
+                    digest,
+                    pinned: pinned_digest(ty, &version_name).map(|pinned| pinned == digest),
+                });
+            }
+        }
+
+        entries.sort_by(|a, b| (&a.version, &a.ty).cmp(&(&b.version, &b.ty)));
+
+        Ok(entries)
+    }
+
+    /// Types of the programs embedded in the binary.
+    pub fn embedded(&self) -> Vec<&'static str> {
+        EMBEDDED_PROGRAMS.iter().map(|(ty, _)| *ty).collect()
+    }
+}
+
+pub async fn download_program(url: &str, path: &Path) -> Result<()> {
+    let res = reqwest::get(url).await?.error_for_status()?;
+
+    // Download to a temporary file, so an interrupted download never leaves a
+    // truncated program behind.
+    let tmp = path.with_extension("download");
+    let mut file = tokio::fs::File::create(&tmp).await?;
+
+    let mut bytes_stream = res.bytes_stream();
+
+    while let Some(chunk) = bytes_stream.next().await {
+        file.write_all(&chunk?).await?;
+    }
+    file.sync_all().await?;
+
+    fs::rename(&tmp, path).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_import_and_get_offline() {
+        let dir = env::temp_dir().join(format!("zktls-registry-{}", std::process::id()));
+        let registry = ProgramRegistry::new(&dir).offline(true);
+        fs::create_dir_all(&dir).await.unwrap();
+
+        // v0.0.1 has no pin.
+        let source = dir.join("local-elf");
+        fs::write(&source, b"elf").await.unwrap();
+        assert!(registry.get("sp1", "v0.0.1").await.is_err());
+        assert!(registry.import("sp1", "v0.0.1", &source).await.is_err());
+
+        let registry = registry.allow_unpinned(true);
+
+        assert!(registry.get("sp1", "v0.0.1").await.is_err());
+
+        let entry = registry.import("sp1", "v0.0.1", &source).await.unwrap();
+        assert_eq!(entry.digest, program_digest(b"elf"));
+        assert_eq!(registry.get("sp1", "v0.0.1").await.unwrap(), b"elf");
+
+        let entries = registry.list().await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].version, "v0.0.1");
+
+        // A corrupted program is not loaded.
+        fs::write(&entry.path, b"corrupted").await.unwrap();
+        assert!(registry.get("sp1", "v0.0.1").await.is_err());
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
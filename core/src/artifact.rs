@@ -0,0 +1,160 @@
+use std::{fs, path::Path, time::Duration};
+
+use alloy_primitives::{Bytes, B256};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{Backend, ProofMetadata, ProofOutput, ProofSystem};
+
+/// Version of the proof artifact format.
+pub const PROOF_ARTIFACT_VERSION: u32 = 1;
+
+/// Encoding of a proof artifact on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtifactFormat {
+    Json,
+    Cbor,
+}
+
+impl ArtifactFormat {
+    /// Format matching the extension of the path, JSON unless it is `.cbor`.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(ext) if ext == "cbor" => ArtifactFormat::Cbor,
+            _ => ArtifactFormat::Json,
+        }
+    }
+}
+
+/// Proof with everything needed to verify it and trace it back to its request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofArtifact {
+    pub version: u32,
+    pub backend: Backend,
+    pub proof_system: ProofSystem,
+    /// Verification key hash for SP1, image ID for RISC0.
+    pub program: B256,
+    pub public_output: Bytes,
+    /// Proof in the encoding of [`ProofOutput::proof`], bincode for core and
+    /// compressed proofs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof: Option<Bytes>,
+    pub proving_time_ms: u64,
+    /// Keccak-256 of the JSON request the proof was generated for.
+    pub request_hash: B256,
+    /// Merkle root of the aggregated public outputs, for aggregated proofs
+    /// committing one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merkle_root: Option<B256>,
+    /// Fingerprint of the trusted root which anchored the certificate chain of
+    /// the server. It is reported by the host, the proof doesn't attest it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trusted_root: Option<B256>,
+}
+
+impl ProofArtifact {
+    pub fn new(output: ProofOutput, request_hash: B256) -> Self {
+        Self {
+            version: PROOF_ARTIFACT_VERSION,
+            backend: output.metadata.backend,
+            proof_system: output.proof_system,
+            program: output.metadata.program,
+            public_output: output.public_output.into(),
+            proof: output.proof.map(Into::into),
+            proving_time_ms: output.metadata.proving_time.as_millis() as u64,
+            request_hash,
+            merkle_root: None,
+            trusted_root: None,
+        }
+    }
+
+    /// Proof of the artifact, to be verified or aggregated.
+    pub fn output(&self) -> ProofOutput {
+        ProofOutput {
+            public_output: self.public_output.to_vec(),
+            proof: self.proof.as_ref().map(|proof| proof.to_vec()),
+            proof_system: self.proof_system,
+            metadata: ProofMetadata {
+                backend: self.backend,
+                program: self.program,
+                proving_time: Duration::from_millis(self.proving_time_ms),
+            },
+        }
+    }
+
+    pub fn encode(&self, format: ArtifactFormat) -> Result<Vec<u8>> {
+        let bytes = match format {
+            ArtifactFormat::Json => serde_json::to_vec_pretty(self)?,
+            ArtifactFormat::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(self, &mut bytes)?;
+                bytes
+            }
+        };
+
+        Ok(bytes)
+    }
+
+    /// Decode an artifact in either format.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let artifact: Self = match bytes.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'{') => serde_json::from_slice(bytes)?,
+            _ => ciborium::from_reader(bytes)?,
+        };
+
+        if artifact.version != PROOF_ARTIFACT_VERSION {
+            return Err(anyhow::anyhow!(
+                "unsupported proof artifact version {}, expected {}",
+                artifact.version,
+                PROOF_ARTIFACT_VERSION
+            ));
+        }
+
+        Ok(artifact)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::decode(&fs::read(path)?)
+    }
+
+    /// Save the artifact, in the format matching the extension of the path.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        fs::write(path, self.encode(ArtifactFormat::from_path(path))?)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        let artifact = ProofArtifact {
+            version: PROOF_ARTIFACT_VERSION,
+            backend: Backend::Sp1,
+            proof_system: ProofSystem::Groth16,
+            program: B256::repeat_byte(1),
+            public_output: Bytes::from_static(b"output"),
+            proof: Some(Bytes::from_static(b"proof")),
+            proving_time_ms: 42,
+            request_hash: B256::repeat_byte(2),
+            merkle_root: None,
+            trusted_root: Some(B256::repeat_byte(3)),
+        };
+
+        for format in [ArtifactFormat::Json, ArtifactFormat::Cbor] {
+            let decoded = ProofArtifact::decode(&artifact.encode(format).unwrap()).unwrap();
+
+            assert_eq!(decoded.backend, artifact.backend);
+            assert_eq!(decoded.program, artifact.program);
+            assert_eq!(decoded.public_output, artifact.public_output);
+            assert_eq!(decoded.proof, artifact.proof);
+        }
+
+        let json = String::from_utf8(artifact.encode(ArtifactFormat::Json).unwrap()).unwrap();
+        assert!(json.contains("\"public_output\": \"0x6f7574707574\""));
+    }
+}
@@ -0,0 +1,267 @@
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+    pin::pin,
+    sync::{
+        atomic::{AtomicU8, AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use tokio::sync::{oneshot, Notify};
+
+/// Error of a proof run by [`spawn_prover`].
+#[derive(Debug, thiserror::Error)]
+pub enum ProveError {
+    #[error("proof did not finish within {0:?}")]
+    DeadlineExceeded(Duration),
+    #[error("prover panicked: {0}")]
+    Panicked(String),
+    #[error("prover task was cancelled")]
+    Cancelled,
+    #[error("guest rejected the input: {0}")]
+    GuestRejected(String),
+}
+
+/// Message of a panic payload, the argument of `panic!`.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match payload.downcast_ref::<&str>() {
+        Some(s) => s.to_string(),
+        None => match payload.downcast_ref::<String>() {
+            Some(s) => s.clone(),
+            None => "unknown panic".to_string(),
+        },
+    }
+}
+
+/// Proof threads still running, including the abandoned ones.
+static RUNNING_PROVERS: AtomicUsize = AtomicUsize::new(0);
+/// Proof threads still running after their caller gave up on them.
+static ABANDONED_PROVERS: AtomicUsize = AtomicUsize::new(0);
+static PROVER_EXITED: Notify = Notify::const_new();
+
+/// States of a proof thread, shared by the thread and its caller.
+const STATE_RUNNING: u8 = 0;
+const STATE_ABANDONED: u8 = 1;
+const STATE_EXITED: u8 = 2;
+
+/// Counts a proof thread as running until it is dropped, even by a panic.
+struct RunningProver {
+    started: Instant,
+    state: Arc<AtomicU8>,
+}
+
+impl RunningProver {
+    fn start() -> Self {
+        RUNNING_PROVERS.fetch_add(1, Ordering::SeqCst);
+        Self {
+            started: Instant::now(),
+            state: Arc::new(AtomicU8::new(STATE_RUNNING)),
+        }
+    }
+}
+
+impl Drop for RunningProver {
+    fn drop(&mut self) {
+        if self.state.swap(STATE_EXITED, Ordering::SeqCst) == STATE_ABANDONED {
+            ABANDONED_PROVERS.fetch_sub(1, Ordering::SeqCst);
+            log::warn!(
+                "abandoned proof exited after {:?}, its slot is free again",
+                self.started.elapsed()
+            );
+        }
+
+        RUNNING_PROVERS.fetch_sub(1, Ordering::SeqCst);
+        PROVER_EXITED.notify_waiters();
+    }
+}
+
+/// Abandons the proof if it is dropped before the caller got its result.
+struct Abandon(Option<Arc<AtomicU8>>);
+
+impl Abandon {
+    fn finish(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for Abandon {
+    fn drop(&mut self) {
+        let Some(state) = self.0.take() else {
+            return;
+        };
+
+        // Counted first, so the thread never sees an abandoned proof which isn't.
+        ABANDONED_PROVERS.fetch_add(1, Ordering::SeqCst);
+        let abandoned = state.compare_exchange(
+            STATE_RUNNING,
+            STATE_ABANDONED,
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+        );
+        if abandoned.is_err() {
+            // The thread already exited.
+            ABANDONED_PROVERS.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Number of proof threads started by [`spawn_prover`] which haven't exited yet.
+pub fn running_provers() -> usize {
+    RUNNING_PROVERS.load(Ordering::SeqCst)
+}
+
+/// Number of running proof threads whose caller gave up on them, after their
+/// deadline or by dropping the future of [`spawn_prover`]. They hold their
+/// slot of [`wait_for_provers`] until they exit.
+pub fn abandoned_provers() -> usize {
+    ABANDONED_PROVERS.load(Ordering::SeqCst)
+}
+
+/// Wait until fewer than `max` proof threads are running.
+///
+/// Callers limiting their concurrency wait here before starting a proof, so a
+/// proof abandoned after its deadline keeps its slot until its thread exits.
+pub async fn wait_for_provers(max: usize) {
+    let mut warned = false;
+
+    loop {
+        let mut exited = pin!(PROVER_EXITED.notified());
+        exited.as_mut().enable();
+
+        if running_provers() < max {
+            return;
+        }
+
+        let abandoned = abandoned_provers();
+        if abandoned > 0 && !warned {
+            log::warn!(
+                "{} of {} proof slots are held by abandoned proofs, waiting for them to exit",
+                abandoned,
+                max
+            );
+            warned = true;
+        }
+
+        exited.await;
+    }
+}
+
+/// Run a CPU-bound proof or execution on a dedicated thread.
+///
+/// The backends can't be interrupted: dropping the returned future or exceeding
+/// the deadline returns immediately, and the proof keeps running until it
+/// finishes and its result is discarded, see [`wait_for_provers`]. Such proofs
+/// are logged and counted by [`abandoned_provers`] until they exit. The thread
+/// is detached, so it doesn't keep the tokio runtime or the process alive once
+/// the caller gives up on it.
+pub async fn spawn_prover<T, F>(deadline: Option<Duration>, f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    let (tx, rx) = oneshot::channel();
+    let runtime = tokio::runtime::Handle::current();
+    let running = RunningProver::start();
+    let abandon = Abandon(Some(running.state.clone()));
+
+    thread::Builder::new()
+        .name("zktls-prover".to_string())
+        .spawn(move || {
+            let _running = running;
+            let _runtime = runtime.enter();
+
+            let _ = tx.send(panic::catch_unwind(AssertUnwindSafe(f)));
+        })?;
+
+    let joined = match deadline {
+        Some(deadline) => match tokio::time::timeout(deadline, rx).await {
+            Ok(joined) => joined,
+            Err(_) => {
+                log::warn!(
+                    "proof exceeded its deadline of {:?}, abandoning it, {} proofs abandoned so far still run",
+                    deadline,
+                    abandoned_provers()
+                );
+                return Err(ProveError::DeadlineExceeded(deadline).into());
+            }
+        },
+        None => rx.await,
+    };
+    abandon.finish();
+
+    match joined {
+        Ok(Ok(result)) => result,
+        Ok(Err(panic)) => Err(ProveError::Panicked(panic_message(panic.as_ref())).into()),
+        Err(_) => Err(ProveError::Cancelled.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap()
+    }
+
+    fn sleep(duration: Duration) -> impl FnOnce() -> Result<()> {
+        move || {
+            thread::sleep(duration);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_spawn_prover() {
+        runtime().block_on(async {
+            let result = spawn_prover(None, || Ok(1)).await.unwrap();
+            assert_eq!(result, 1);
+
+            let deadline = Some(Duration::from_millis(10));
+            let e = spawn_prover(deadline, sleep(Duration::from_millis(500)))
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                e.downcast_ref::<ProveError>(),
+                Some(ProveError::DeadlineExceeded(_))
+            ));
+
+            // The abandoned proof holds its slot until its thread exits.
+            assert_eq!(running_provers(), 1);
+            assert_eq!(abandoned_provers(), 1);
+            tokio::time::timeout(Duration::from_secs(5), wait_for_provers(1))
+                .await
+                .unwrap();
+            assert_eq!(running_provers(), 0);
+            assert_eq!(abandoned_provers(), 0);
+
+            let e = spawn_prover(None, || -> Result<()> { panic!("guest rejected") })
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                e.downcast_ref::<ProveError>(),
+                Some(ProveError::Panicked(message)) if message == "guest rejected"
+            ));
+        });
+
+        // Shutting down the runtime doesn't wait for an abandoned proof.
+        let begin = Instant::now();
+        let runtime = runtime();
+        let deadline = Some(Duration::from_millis(10));
+        let e = runtime
+            .block_on(spawn_prover(deadline, sleep(Duration::from_millis(500))))
+            .unwrap_err();
+        assert!(e.downcast_ref::<ProveError>().is_some());
+        drop(runtime);
+        assert!(begin.elapsed() < Duration::from_millis(400));
+    }
+}
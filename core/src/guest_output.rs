@@ -0,0 +1,36 @@
+use alloy_sol_types::{sol, SolValue};
+use anyhow::Result;
+
+sol! {
+    /// Public output of the zkTLS program, ABI-encoded as a single tuple.
+    ///
+    /// This is the layout `ZkTLSOutput.decode` of the exported verifier
+    /// contracts expects, both definitions must be kept in sync.
+    #[derive(Debug, Default, PartialEq, Eq)]
+    struct GuestOutput {
+        /// Keccak-256 of the HTTP request sent to the server.
+        bytes32 requestHash;
+        /// `target.client` of the request.
+        address client;
+        /// `target.prover_id` of the request.
+        bytes32 proverId;
+        /// `target.submit_network_id` of the request.
+        uint64 submitNetworkId;
+        /// Time of the response, in seconds since the Unix epoch.
+        uint64 responseTime;
+        /// Parts of the response selected by the response template.
+        bytes[] responses;
+    }
+}
+
+impl GuestOutput {
+    /// Decode the public output of a proof, as `abi.decode(output, (GuestOutput))`.
+    pub fn decode(public_output: &[u8]) -> Result<Self> {
+        Ok(<Self as SolValue>::abi_decode(public_output, true)?)
+    }
+
+    /// Encode the output as `abi.encode(output)`.
+    pub fn encode(&self) -> Vec<u8> {
+        SolValue::abi_encode(self)
+    }
+}
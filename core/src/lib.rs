@@ -1,2 +1,16 @@
 mod prelude;
 pub use prelude::*;
+
+mod output;
+pub use output::*;
+
+mod blocking;
+pub use blocking::*;
+
+mod artifact;
+pub use artifact::*;
+
+mod guest_output;
+pub use guest_output::*;
+
+pub use zktls_aggregation::*;
@@ -0,0 +1,137 @@
+use std::{collections::BTreeMap, fmt, str::FromStr, time::Duration};
+
+use alloy_primitives::B256;
+use serde::{Deserialize, Serialize};
+
+/// zkVM which generated a proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Backend {
+    Sp1,
+    R0,
+}
+
+/// Kind of proof requested from a [`ZkProver`](crate::ZkProver).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofMode {
+    /// STARK proof of every shard or segment, the cheapest one to generate.
+    Core,
+    /// STARK proof recursively compressed to a constant size.
+    Compressed,
+    /// Groth16 SNARK, verifiable on-chain.
+    #[default]
+    Groth16,
+    /// PLONK SNARK, verifiable on-chain without a trusted setup ceremony.
+    Plonk,
+}
+
+impl fmt::Display for ProofMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ProofMode::Core => "core",
+            ProofMode::Compressed => "compressed",
+            ProofMode::Groth16 => "groth16",
+            ProofMode::Plonk => "plonk",
+        };
+
+        f.write_str(name)
+    }
+}
+
+impl FromStr for ProofMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "core" => Ok(ProofMode::Core),
+            "compressed" => Ok(ProofMode::Compressed),
+            "groth16" => Ok(ProofMode::Groth16),
+            "plonk" => Ok(ProofMode::Plonk),
+            _ => Err(anyhow::anyhow!(
+                "unknown proof mode {}, expected core, compressed, groth16 or plonk",
+                s
+            )),
+        }
+    }
+}
+
+/// Proof system of a proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofSystem {
+    Core,
+    Groth16,
+    Plonk,
+    /// Recursively compressed STARK proof, verified off-chain or by another program.
+    Compressed,
+    /// No proof was generated, only the public output is meaningful.
+    Mock,
+}
+
+/// Output of a [`ZkProver`](crate::ZkProver).
+#[derive(Debug, Clone)]
+pub struct ProofOutput {
+    /// Public output committed by the program.
+    pub public_output: Vec<u8>,
+    /// Proof in the encoding of the backend verifier, `None` for mock proofs.
+    ///
+    /// Groth16 and PLONK proofs are in the encoding of the verifier contracts and
+    /// start with the 4-byte selector of the verifier.
+    ///
+    /// Core and compressed proofs are encoded with bincode 1 and its default
+    /// options: an `SP1ProofWithPublicValues` for SP1, a `risc0_zkvm::Receipt`
+    /// with its composite or succinct inner receipt and the journal for RISC0.
+    /// Both embed the public output, which the verifiers check against
+    /// `public_output`. The encoding is tied to the SDK version of the backend.
+    pub proof: Option<Vec<u8>>,
+    pub proof_system: ProofSystem,
+    pub metadata: ProofMetadata,
+}
+
+/// Information about how a proof was generated.
+#[derive(Debug, Clone)]
+pub struct ProofMetadata {
+    pub backend: Backend,
+    /// Verification key hash for SP1, image ID for RISC0.
+    pub program: B256,
+    pub proving_time: Duration,
+}
+
+impl From<ProofMode> for ProofSystem {
+    fn from(mode: ProofMode) -> Self {
+        match mode {
+            ProofMode::Core => ProofSystem::Core,
+            ProofMode::Compressed => ProofSystem::Compressed,
+            ProofMode::Groth16 => ProofSystem::Groth16,
+            ProofMode::Plonk => ProofSystem::Plonk,
+        }
+    }
+}
+
+/// Output of a [`ZkExecutor`](crate::ZkExecutor), the program is run without proving.
+#[derive(Debug, Clone)]
+pub struct ExecutionOutput {
+    pub backend: Backend,
+    /// Public output committed by the program.
+    pub public_output: Vec<u8>,
+    /// Cycles of the execution: the executed instructions for SP1, the cycles
+    /// including the padding of the segments for RISC0.
+    pub total_cycles: u64,
+    /// Cycles executed by the program without padding, RISC0 only: SP1 doesn't
+    /// pad its execution, so its total cycles are the user cycles.
+    pub user_cycles: Option<u64>,
+    /// Number of segments for RISC0, of shards for SP1. SP1 only splits the
+    /// execution when proving, its shards are computed from the cycles and the
+    /// shard size of the prover.
+    pub segments: u64,
+    /// Cycles of the regions the program marks with `cycle-tracker-start` and
+    /// `cycle-tracker-end`, by region name. SP1 only, empty for RISC0.
+    pub cycle_tracker: BTreeMap<String, u64>,
+}
+
+impl ProofOutput {
+    pub fn is_mock(&self) -> bool {
+        self.proof_system == ProofSystem::Mock
+    }
+}
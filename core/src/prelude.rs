@@ -3,16 +3,47 @@ use std::future::Future;
 use anyhow::Result;
 use zktls_program_core::{GuestInput, Request};
 
+use crate::{AggregationCommitment, ExecutionOutput, ProofMode, ProofOutput};
+
 /// Build the input for the zktls program.
 pub trait InputBuilder {
     fn build_input(&mut self, request: Request) -> impl Future<Output = Result<GuestInput>> + Send;
 }
 
+/// Execute the program on the input without proving, to check it is accepted and what it costs.
+///
+/// A rejected input is an error with the panic message of the program.
+pub trait ZkExecutor {
+    fn execute(
+        &mut self,
+        input: GuestInput,
+        guest_program: &[u8],
+    ) -> impl Future<Output = Result<ExecutionOutput>> + Send;
+}
+
 /// Prove the request using the zk prover.
+///
+/// Backends return an error for the proof modes they don't support.
 pub trait ZkProver {
     fn prove(
         &mut self,
         input: GuestInput,
         guest_program: &[u8],
-    ) -> impl Future<Output = Result<(Vec<u8>, Vec<u8>)>> + Send;
+        mode: ProofMode,
+    ) -> impl Future<Output = Result<ProofOutput>> + Send;
+}
+
+/// Prove many proofs of the zkTLS program with a single proof of an aggregation program.
+///
+/// The proofs must be compressed proofs of `guest_program`, the aggregation
+/// program reads an [`AggregationInput`](crate::AggregationInput) and verifies them.
+pub trait ZkAggregator {
+    fn aggregate(
+        &mut self,
+        proofs: Vec<ProofOutput>,
+        guest_program: &[u8],
+        aggregation_program: &[u8],
+        commitment: AggregationCommitment,
+        mode: ProofMode,
+    ) -> impl Future<Output = Result<ProofOutput>> + Send;
 }
@@ -0,0 +1,307 @@
+use std::{fmt, str::FromStr};
+
+use alloy_primitives::{Keccak256, B256};
+use serde::{Deserialize, Serialize};
+
+/// What an aggregation program commits for the proofs it verified.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregationCommitment {
+    /// Merkle root of the public outputs, see [`merkle_root`].
+    #[default]
+    MerkleRoot,
+    /// Every public output, each prefixed by its length as a big-endian u32.
+    Journals,
+}
+
+impl fmt::Display for AggregationCommitment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AggregationCommitment::MerkleRoot => "merkle-root",
+            AggregationCommitment::Journals => "journals",
+        };
+
+        f.write_str(name)
+    }
+}
+
+impl FromStr for AggregationCommitment {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "merkle-root" => Ok(AggregationCommitment::MerkleRoot),
+            "journals" => Ok(AggregationCommitment::Journals),
+            _ => Err(anyhow::anyhow!(
+                "unknown aggregation commitment {}, expected merkle-root or journals",
+                s
+            )),
+        }
+    }
+}
+
+/// Input of an aggregation program.
+///
+/// The program reads it, verifies a proof of `program` for each public output,
+/// and commits [`AggregationInput::expected_output`], see [`run_aggregation`].
+/// SP1 programs verify them with `verify_sp1_proof`, RISC0 programs with
+/// `env::verify`, the proofs being written to the stdin or added as
+/// assumptions by the prover.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregationInput {
+    /// Verification key digest for SP1, image ID for RISC0, of the zkTLS program.
+    pub program: [u32; 8],
+    pub public_outputs: Vec<Vec<u8>>,
+    pub commitment: AggregationCommitment,
+}
+
+impl AggregationInput {
+    /// Public output of the aggregation program: the words of `program` as
+    /// little-endian bytes, then the commitment.
+    pub fn expected_output(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = self.program.iter().flat_map(|w| w.to_le_bytes()).collect();
+
+        match self.commitment {
+            AggregationCommitment::MerkleRoot => {
+                output.extend_from_slice(merkle_root(&self.public_outputs).as_slice());
+            }
+            AggregationCommitment::Journals => {
+                for public_output in &self.public_outputs {
+                    output.extend_from_slice(&(public_output.len() as u32).to_be_bytes());
+                    output.extend_from_slice(public_output);
+                }
+            }
+        }
+
+        output
+    }
+}
+
+/// Interface of an aggregation program to its zkVM.
+pub trait AggregationEnv {
+    /// Read the [`AggregationInput`] written by the prover.
+    fn read_input(&mut self) -> AggregationInput;
+
+    /// Verify a proof of `program` committing `public_output`, panicking if
+    /// the prover provided none.
+    fn verify(&mut self, program: &[u32; 8], public_output: &[u8]);
+
+    /// Commit `output` as the public output of the aggregation program.
+    fn commit(&mut self, output: &[u8]);
+}
+
+/// Body of the aggregation programs: verify a proof of every public output of
+/// the input, then commit [`AggregationInput::expected_output`].
+pub fn run_aggregation(env: &mut impl AggregationEnv) {
+    let input = env.read_input();
+
+    for public_output in &input.public_outputs {
+        env.verify(&input.program, public_output);
+    }
+
+    env.commit(&input.expected_output());
+}
+
+/// First byte hashed for a leaf of the Merkle tree.
+const MERKLE_LEAF_PREFIX: u8 = 0x00;
+/// First byte hashed for an internal node of the Merkle tree, so that a node
+/// can't be passed off as a leaf.
+const MERKLE_NODE_PREFIX: u8 = 0x01;
+
+/// Leaf of a public output: `keccak256(0x00 || public_output)`.
+pub fn merkle_leaf(public_output: &[u8]) -> B256 {
+    let mut hasher = Keccak256::new();
+    hasher.update([MERKLE_LEAF_PREFIX]);
+    hasher.update(public_output);
+    hasher.finalize()
+}
+
+/// Parent of two nodes: `keccak256(0x01 || min(a, b) || max(a, b))`.
+pub fn merkle_node(a: B256, b: B256) -> B256 {
+    let (a, b) = if a <= b { (a, b) } else { (b, a) };
+
+    let mut hasher = Keccak256::new();
+    hasher.update([MERKLE_NODE_PREFIX]);
+    hasher.update(a);
+    hasher.update(b);
+    hasher.finalize()
+}
+
+/// Merkle tree of the public outputs.
+///
+/// Leaves are [`merkle_leaf`] of the public outputs, nodes are [`merkle_node`]
+/// of their children, and the last node of an odd level is moved up as is.
+fn merkle_levels(public_outputs: &[Vec<u8>]) -> Vec<Vec<B256>> {
+    let mut levels = vec![public_outputs
+        .iter()
+        .map(|output| merkle_leaf(output))
+        .collect::<Vec<_>>()];
+
+    while levels.last().expect("at least one level").len() > 1 {
+        let level = levels.last().expect("at least one level");
+        let next = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => merkle_node(*a, *b),
+                [a] => *a,
+                _ => unreachable!("chunks of 2"),
+            })
+            .collect();
+        levels.push(next);
+    }
+
+    levels
+}
+
+/// Merkle root of the public outputs, zero if there are none.
+pub fn merkle_root(public_outputs: &[Vec<u8>]) -> B256 {
+    merkle_levels(public_outputs)
+        .last()
+        .and_then(|level| level.first().copied())
+        .unwrap_or_default()
+}
+
+/// Siblings proving the public output at `index` is in the tree of [`merkle_root`].
+///
+/// The root is [`merkle_node`] folded over the siblings, starting from the
+/// [`merkle_leaf`] of the public output.
+pub fn merkle_proof(public_outputs: &[Vec<u8>], index: usize) -> Vec<B256> {
+    let levels = merkle_levels(public_outputs);
+
+    let mut proof = Vec::new();
+    let mut index = index;
+    for level in &levels[..levels.len() - 1] {
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push(*sibling);
+        }
+        index /= 2;
+    }
+
+    proof
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::keccak256;
+
+    use super::*;
+
+    #[test]
+    fn test_merkle_proof() {
+        let outputs: Vec<Vec<u8>> = (0..5u8).map(|i| vec![i; 3]).collect();
+        let root = merkle_root(&outputs);
+
+        for (i, output) in outputs.iter().enumerate() {
+            let leaf = merkle_proof(&outputs, i)
+                .into_iter()
+                .fold(merkle_leaf(output), merkle_node);
+            assert_eq!(leaf, root);
+        }
+
+        assert_eq!(merkle_root(&outputs[..1]), merkle_leaf(&outputs[0]));
+        assert_eq!(merkle_root(&[]), B256::ZERO);
+    }
+
+    #[test]
+    fn test_merkle_domain_separation() {
+        let outputs: Vec<Vec<u8>> = (0..4u8).map(|i| vec![i; 3]).collect();
+        let root = merkle_root(&outputs);
+
+        // The preimage of the parent of the first two leaves, submitted as a
+        // public output with the rest of the proof of the first leaf.
+        let (a, b) = (merkle_leaf(&outputs[0]), merkle_leaf(&outputs[1]));
+        let (a, b) = if a <= b { (a, b) } else { (b, a) };
+        let node = [a.as_slice(), b.as_slice()].concat();
+
+        let forged = merkle_proof(&outputs, 0)[1..]
+            .iter()
+            .fold(merkle_leaf(&node), |node, sibling| {
+                merkle_node(node, *sibling)
+            });
+        assert_ne!(forged, root);
+        assert_ne!(merkle_leaf(&node), merkle_node(a, b));
+
+        assert_eq!(merkle_leaf(b"ab"), keccak256(b"\x00ab"));
+    }
+
+    /// Runs [`run_aggregation`] as the aggregation programs do, with a mock
+    /// verifier accepting the proofs provided by the test.
+    struct MockEnv {
+        input: AggregationInput,
+        proofs: Vec<([u32; 8], Vec<u8>)>,
+        committed: Vec<u8>,
+    }
+
+    impl AggregationEnv for MockEnv {
+        fn read_input(&mut self) -> AggregationInput {
+            self.input.clone()
+        }
+
+        fn verify(&mut self, program: &[u32; 8], public_output: &[u8]) {
+            let proof = (*program, public_output.to_vec());
+            let i = self
+                .proofs
+                .iter()
+                .position(|p| *p == proof)
+                .expect("no proof for the public output");
+            self.proofs.remove(i);
+        }
+
+        fn commit(&mut self, output: &[u8]) {
+            self.committed.extend_from_slice(output);
+        }
+    }
+
+    #[test]
+    fn test_run_aggregation_mock() {
+        for commitment in [
+            AggregationCommitment::MerkleRoot,
+            AggregationCommitment::Journals,
+        ] {
+            let public_outputs: Vec<Vec<u8>> = (0..3u8).map(|i| vec![i; 5]).collect();
+            let input = AggregationInput {
+                program: [7; 8],
+                public_outputs: public_outputs.clone(),
+                commitment,
+            };
+
+            let mut env = MockEnv {
+                input: input.clone(),
+                proofs: public_outputs.into_iter().map(|o| ([7; 8], o)).collect(),
+                committed: Vec::new(),
+            };
+            run_aggregation(&mut env);
+
+            assert!(env.proofs.is_empty());
+            assert_eq!(env.committed, input.expected_output());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "no proof for the public output")]
+    fn test_run_aggregation_unproven() {
+        let mut env = MockEnv {
+            input: AggregationInput {
+                program: [7; 8],
+                public_outputs: vec![b"ab".to_vec()],
+                commitment: AggregationCommitment::MerkleRoot,
+            },
+            proofs: vec![([8; 8], b"ab".to_vec())],
+            committed: Vec::new(),
+        };
+        run_aggregation(&mut env);
+    }
+
+    #[test]
+    fn test_expected_output() {
+        let input = AggregationInput {
+            program: [1; 8],
+            public_outputs: vec![b"ab".to_vec()],
+            commitment: AggregationCommitment::Journals,
+        };
+
+        let output = input.expected_output();
+        assert_eq!(&output[..4], &[1, 0, 0, 0]);
+        assert_eq!(&output[32..], &[0, 0, 0, 2, b'a', b'b']);
+    }
+}
@@ -0,0 +1,91 @@
+use std::{future::Future, rc::Rc};
+
+use anyhow::{anyhow, Result};
+use risc0_zkvm::{compute_image_id, ExecutorEnv, InnerReceipt, Prover, ProverOpts, Receipt};
+use zktls_core::{
+    spawn_prover, AggregationCommitment, AggregationInput, ProofMode, ProofOutput, ZkAggregator,
+};
+
+use crate::{prove_env, prover_opts, Risc0GuestProver};
+
+impl ZkAggregator for Risc0GuestProver {
+    fn aggregate(
+        &mut self,
+        proofs: Vec<ProofOutput>,
+        guest_program: &[u8],
+        aggregation_program: &[u8],
+        commitment: AggregationCommitment,
+        mode: ProofMode,
+    ) -> impl Future<Output = Result<ProofOutput>> + Send {
+        let guest_program = guest_program.to_vec();
+        let aggregation_program = aggregation_program.to_vec();
+        let prover_type = self.mode;
+
+        spawn_prover(self.deadline, move || {
+            let opts = prover_type.prover_opts(prover_opts(mode)?);
+            aggregate(
+                prover_type.prover(),
+                proofs,
+                &guest_program,
+                &aggregation_program,
+                commitment,
+                mode,
+                &opts,
+            )
+        })
+    }
+}
+
+/// Verify succinct receipts of the guest program in the aggregation program.
+///
+/// The receipts are added as assumptions of the [`AggregationInput`], the
+/// aggregation program resolves them with `env::verify`.
+pub fn aggregate(
+    prover: Rc<dyn Prover>,
+    proofs: Vec<ProofOutput>,
+    guest_program: &[u8],
+    aggregation_program: &[u8],
+    commitment: AggregationCommitment,
+    mode: ProofMode,
+    opts: &ProverOpts,
+) -> Result<ProofOutput> {
+    let image_id = compute_image_id(guest_program)?;
+
+    let input = AggregationInput {
+        program: image_id.as_words().try_into()?,
+        public_outputs: proofs.iter().map(|p| p.public_output.clone()).collect(),
+        commitment,
+    };
+
+    let mut env = ExecutorEnv::builder();
+    env.write(&input)?;
+
+    for (i, proof) in proofs.into_iter().enumerate() {
+        if proof.metadata.program.as_slice() != image_id.as_bytes() {
+            return Err(anyhow!("proof #{} is not a proof of the program", i));
+        }
+
+        let encoded = proof
+            .proof
+            .ok_or_else(|| anyhow!("proof #{} is a mock proof, it can't be aggregated", i))?;
+        let receipt: Receipt = bincode::deserialize(&encoded)?;
+
+        if !matches!(receipt.inner, InnerReceipt::Succinct(_)) {
+            return Err(anyhow!(
+                "proof #{} is not a compressed proof, only those can be aggregated",
+                i
+            ));
+        }
+        env.add_assumption(receipt);
+    }
+
+    let output = prove_env(prover, env.build()?, aggregation_program, mode, opts)?;
+
+    if output.public_output != input.expected_output() {
+        return Err(anyhow!(
+            "aggregation program committed an unexpected output, it doesn't follow the aggregation input"
+        ));
+    }
+
+    Ok(output)
+}
@@ -0,0 +1,48 @@
+use std::future::Future;
+
+use anyhow::Result;
+use risc0_zkvm::{default_executor, ExecutorEnv, ExitCode};
+use zktls_core::{spawn_prover, Backend, ExecutionOutput, ProveError, ZkExecutor};
+use zktls_program_core::GuestInput;
+
+use crate::{input_bytes, Risc0GuestProver};
+
+impl ZkExecutor for Risc0GuestProver {
+    fn execute(
+        &mut self,
+        input: GuestInput,
+        guest_program: &[u8],
+    ) -> impl Future<Output = Result<ExecutionOutput>> + Send {
+        let guest_program = guest_program.to_vec();
+
+        spawn_prover(self.deadline, move || execute(input, &guest_program))
+    }
+}
+
+/// Execute the guest program with the RISC0 executor.
+pub fn execute(input: GuestInput, guest_program: &[u8]) -> Result<ExecutionOutput> {
+    let env = ExecutorEnv::builder()
+        .write_slice(&input_bytes(&input)?)
+        .build()?;
+
+    let session = default_executor()
+        .execute(env, guest_program)
+        .map_err(|e| ProveError::GuestRejected(e.to_string()))?;
+
+    if session.exit_code != ExitCode::Halted(0) {
+        return Err(ProveError::GuestRejected(format!("exit code {:?}", session.exit_code)).into());
+    }
+
+    let total_cycles = session.segments.iter().map(|s| 1u64 << s.po2).sum();
+    let user_cycles = session.segments.iter().map(|s| s.cycles as u64).sum();
+    log::info!("executed {} user cycles", user_cycles);
+
+    Ok(ExecutionOutput {
+        backend: Backend::R0,
+        public_output: session.journal.bytes,
+        total_cycles,
+        user_cycles: Some(user_cycles),
+        segments: session.segments.len() as u64,
+        cycle_tracker: Default::default(),
+    })
+}
@@ -1,2 +1,14 @@
 mod prover;
 pub use prover::*;
+
+mod program;
+pub use program::*;
+
+mod verify;
+pub use verify::*;
+
+mod execute;
+pub use execute::*;
+
+mod aggregate;
+pub use aggregate::*;
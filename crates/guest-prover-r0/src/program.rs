@@ -0,0 +1,43 @@
+use anyhow::Result;
+use risc0_zkvm::{compute_image_id, sha::Digestible, Groth16ReceiptVerifierParameters};
+
+/// Image ID of the guest program.
+pub fn image_id(guest_program: &[u8]) -> Result<[u8; 32]> {
+    let image_id = compute_image_id(guest_program)?;
+
+    Ok(image_id.as_bytes().try_into()?)
+}
+
+/// Control root and BN254 control ID accepted by the Groth16 verifier.
+///
+/// The BN254 control ID is in the byte order used by the Solidity verifier,
+/// which is the opposite of the one in risc0.
+pub fn groth16_control_ids() -> ([u8; 32], [u8; 32]) {
+    let params = Groth16ReceiptVerifierParameters::default();
+
+    let control_root = params
+        .control_root
+        .as_bytes()
+        .try_into()
+        .expect("digest is 32 bytes");
+
+    let mut bn254_control_id: [u8; 32] = params
+        .bn254_control_id
+        .as_bytes()
+        .try_into()
+        .expect("digest is 32 bytes");
+    bn254_control_id.reverse();
+
+    (control_root, bn254_control_id)
+}
+
+/// Selector of the Groth16 verifier, the first 4 bytes of its parameters digest.
+///
+/// The RISC0 verifier router uses it to dispatch a seal to its verifier.
+pub fn verifier_selector() -> [u8; 4] {
+    let digest = Groth16ReceiptVerifierParameters::default().digest();
+
+    digest.as_bytes()[..4]
+        .try_into()
+        .expect("digest is 32 bytes")
+}
@@ -1,12 +1,16 @@
-use std::{future::Future, panic};
+use std::{future::Future, rc::Rc, time::Duration};
 
 use alloy_primitives::hex;
 use anyhow::Result;
-use risc0_zkvm::{default_prover, ExecutorEnv, ProverOpts};
-use zktls_core::ZkProver;
+use risc0_zkvm::{BonsaiProver, ExecutorEnv, InnerReceipt, LocalProver, Prover, ProverOpts};
+use zktls_core::{
+    spawn_prover, Backend, ProofMetadata, ProofMode, ProofOutput, ProofSystem, ZkProver,
+};
 use zktls_program_core::GuestInput;
 
-#[derive(Default)]
+use crate::{image_id, verifier_selector};
+
+#[derive(Debug, Clone, Copy, Default)]
 pub enum ProverType {
     #[default]
     Mock,
@@ -17,23 +21,37 @@ pub enum ProverType {
 }
 
 impl ProverType {
-    pub fn set_env(&self) {
+    /// Build the prover of this mode, ignoring `RISC0_PROVER`.
+    ///
+    /// The network prover reads its credentials from `BONSAI_API_URL` and `BONSAI_API_KEY`.
+    pub fn prover(&self) -> Rc<dyn Prover> {
         match self {
-            ProverType::Mock => std::env::set_var("RISC0_DEV_MODE", "true"),
-            ProverType::Local => std::env::set_var("RISC0_PROVER", "local"),
+            ProverType::Mock | ProverType::Local => Rc::new(LocalProver::new("local")),
             #[cfg(feature = "cuda")]
-            ProverType::Cuda => std::env::set_var("RISC0_PROVER", "local"),
-            ProverType::Network => std::env::set_var("RISC0_PROVER", "bonsai"),
+            ProverType::Cuda => Rc::new(LocalProver::new("local")),
+            ProverType::Network => Rc::new(BonsaiProver::new("bonsai")),
         }
     }
+
+    /// Enable the dev mode of the options in mock mode, ignoring `RISC0_DEV_MODE`.
+    pub fn prover_opts(&self, opts: ProverOpts) -> ProverOpts {
+        opts.with_dev_mode(matches!(self, ProverType::Mock))
+    }
 }
 
 #[derive(Default)]
 pub struct Risc0GuestProver {
-    mode: ProverType,
+    pub(crate) mode: ProverType,
+    pub(crate) deadline: Option<Duration>,
 }
 
 impl Risc0GuestProver {
+    /// Fail proofs and executions which take longer than `deadline`.
+    pub fn deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
     pub fn mock(mut self) -> Self {
         self.mode = ProverType::Mock;
         self
@@ -61,46 +79,91 @@ impl ZkProver for Risc0GuestProver {
         &mut self,
         input: GuestInput,
         guest_program: &[u8],
-    ) -> impl Future<Output = Result<(Vec<u8>, Vec<u8>)>> + Send {
-        self.mode.set_env();
-        panic_catched_prover(input, guest_program)
+        mode: ProofMode,
+    ) -> impl Future<Output = Result<ProofOutput>> + Send {
+        let guest_program = guest_program.to_vec();
+        let prover_type = self.mode;
+
+        spawn_prover(self.deadline, move || {
+            let opts = prover_type.prover_opts(prover_opts(mode)?);
+            prover(prover_type.prover(), input, &guest_program, mode, &opts)
+        })
     }
 }
 
-async fn panic_catched_prover(
-    input: GuestInput,
-    guest_program: &[u8],
-) -> Result<(Vec<u8>, Vec<u8>)> {
-    panic::catch_unwind(move || prover(input, guest_program))
-        .map_err(|e| anyhow::anyhow!("{:?}", e))?
+pub(crate) fn input_bytes(input: &GuestInput) -> Result<Vec<u8>> {
+    let mut input_bytes = Vec::new();
+    ciborium::into_writer(input, &mut input_bytes)?;
+
+    Ok(input_bytes)
 }
 
-fn prover(input: GuestInput, guest_program: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
-    let prover = default_prover();
+pub(crate) fn prover_opts(mode: ProofMode) -> Result<ProverOpts> {
+    match mode {
+        ProofMode::Core => Ok(ProverOpts::composite()),
+        ProofMode::Compressed => Ok(ProverOpts::succinct()),
+        ProofMode::Groth16 => Ok(ProverOpts::groth16()),
+        ProofMode::Plonk => Err(anyhow::anyhow!("RISC0 doesn't support PLONK proofs")),
+    }
+}
 
-    let mut input_bytes = Vec::new();
-    ciborium::into_writer(&input, &mut input_bytes)?;
+fn prover(
+    prover: Rc<dyn Prover>,
+    input: GuestInput,
+    guest_program: &[u8],
+    mode: ProofMode,
+    opts: &ProverOpts,
+) -> Result<ProofOutput> {
+    let input_bytes = input_bytes(&input)?;
 
-    println!("input_len: {:?}", input_bytes.len());
+    log::debug!("input length: {}", input_bytes.len());
 
     let env = ExecutorEnv::builder().write_slice(&input_bytes).build()?;
 
+    prove_env(prover, env, guest_program, mode, opts)
+}
+
+/// Prove the program in `env`.
+pub(crate) fn prove_env(
+    prover: Rc<dyn Prover>,
+    env: ExecutorEnv<'_>,
+    guest_program: &[u8],
+    mode: ProofMode,
+    opts: &ProverOpts,
+) -> Result<ProofOutput> {
     let start = std::time::Instant::now();
 
-    let prove_result = prover.prove_with_opts(env, guest_program, &ProverOpts::groth16())?;
+    let prove_result = prover.prove_with_opts(env, guest_program, opts)?;
 
     let elapsed = start.elapsed();
-    println!("Proving took: {:?}", elapsed);
-
-    let journal = prove_result.receipt.journal.bytes;
-    let mut proof = prove_result.receipt.inner.groth16()?.seal.clone();
-
-    log::info!("output: {}", hex::encode(&journal));
-    log::info!("proof: {}", hex::encode(&proof));
-
-    if proof.len() <= 4 {
-        proof = Vec::new();
-    }
-
-    Ok((journal, proof))
+    log::info!("proving took {:?}", elapsed);
+
+    let receipt = prove_result.receipt;
+    log::info!("output: {}", hex::encode(&receipt.journal.bytes));
+
+    let (proof, proof_system) = match &receipt.inner {
+        InnerReceipt::Groth16(groth16) => {
+            // The EVM verifier expects the seal to start with the selector.
+            let mut proof = verifier_selector().to_vec();
+            proof.extend_from_slice(&groth16.seal);
+            log::info!("proof: {}", hex::encode(&proof));
+            (Some(proof), ProofSystem::Groth16)
+        }
+        InnerReceipt::Fake(_) => (None, ProofSystem::Mock),
+        // Composite and succinct receipts have no EVM encoding, the whole receipt
+        // is kept with bincode so that `verify` can decode it back, see
+        // `ProofOutput::proof`.
+        _ => (Some(bincode::serialize(&receipt)?), mode.into()),
+    };
+
+    Ok(ProofOutput {
+        public_output: receipt.journal.bytes,
+        proof,
+        proof_system,
+        metadata: ProofMetadata {
+            backend: Backend::R0,
+            program: image_id(guest_program)?.into(),
+            proving_time: elapsed,
+        },
+    })
 }
@@ -0,0 +1,58 @@
+use anyhow::{anyhow, Result};
+use risc0_zkvm::{
+    sha::Digestible, Groth16Receipt, Groth16ReceiptVerifierParameters, InnerReceipt, Receipt,
+    ReceiptClaim,
+};
+use zktls_core::ProofSystem;
+
+use crate::{image_id, verifier_selector};
+
+/// Verify a proof of the guest program against its journal.
+///
+/// The proof is the one returned by the prover: the Groth16 seal prefixed with
+/// the verifier selector, or for core and compressed proofs the whole
+/// `Receipt` (composite or succinct inner receipt and journal) encoded with
+/// bincode 1 and its default options.
+pub fn verify(
+    guest_program: &[u8],
+    journal: &[u8],
+    proof: &[u8],
+    proof_system: ProofSystem,
+) -> Result<()> {
+    if proof.is_empty() || proof_system == ProofSystem::Mock {
+        return Err(anyhow!("empty proof, mock proofs can't be verified"));
+    }
+
+    let image_id = image_id(guest_program)?;
+
+    let receipt = match proof_system {
+        ProofSystem::Groth16 => {
+            let seal = proof
+                .strip_prefix(&verifier_selector())
+                .ok_or_else(|| anyhow!("seal doesn't start with the Groth16 verifier selector"))?;
+
+            let claim = ReceiptClaim::ok(image_id, journal.to_vec());
+
+            Receipt::new(
+                InnerReceipt::Groth16(Groth16Receipt::new(
+                    seal.to_vec(),
+                    claim.into(),
+                    Groth16ReceiptVerifierParameters::default().digest(),
+                )),
+                journal.to_vec(),
+            )
+        }
+        ProofSystem::Plonk => return Err(anyhow!("RISC0 doesn't support PLONK proofs")),
+        _ => {
+            let receipt: Receipt = bincode::deserialize(proof)?;
+            if receipt.journal.bytes != journal {
+                return Err(anyhow!("journal of the receipt doesn't match the output"));
+            }
+            receipt
+        }
+    };
+
+    receipt
+        .verify(image_id)
+        .map_err(|e| anyhow!("invalid proof: {}", e))
+}
@@ -0,0 +1,115 @@
+use std::future::Future;
+
+use anyhow::{anyhow, Result};
+use sp1_prover::components::CpuProverComponents;
+use sp1_sdk::{HashableKey, Prover, SP1Proof, SP1ProofWithPublicValues, SP1Stdin};
+use zktls_core::{
+    spawn_prover, AggregationCommitment, AggregationInput, ProofMode, ProofOutput, ZkAggregator,
+};
+
+use crate::{prove_stdin, with_client, ClientTask, SP1GuestProver};
+
+impl ZkAggregator for SP1GuestProver {
+    fn aggregate(
+        &mut self,
+        proofs: Vec<ProofOutput>,
+        guest_program: &[u8],
+        aggregation_program: &[u8],
+        commitment: AggregationCommitment,
+        mode: ProofMode,
+    ) -> impl Future<Output = Result<ProofOutput>> + Send {
+        let task = AggregateTask {
+            proofs,
+            guest_program: guest_program.to_vec(),
+            aggregation_program: aggregation_program.to_vec(),
+            commitment,
+            mode,
+        };
+        let prover_type = self.mode;
+        let moongate_server = self.moongate_server.clone();
+
+        spawn_prover(self.deadline, move || {
+            with_client(prover_type, moongate_server, task)
+        })
+    }
+}
+
+struct AggregateTask {
+    proofs: Vec<ProofOutput>,
+    guest_program: Vec<u8>,
+    aggregation_program: Vec<u8>,
+    commitment: AggregationCommitment,
+    mode: ProofMode,
+}
+
+impl ClientTask for AggregateTask {
+    type Output = ProofOutput;
+
+    fn run<P: Prover<CpuProverComponents>>(self, client: P) -> Result<ProofOutput> {
+        aggregate(
+            client,
+            self.proofs,
+            &self.guest_program,
+            &self.aggregation_program,
+            self.commitment,
+            self.mode,
+        )
+    }
+}
+
+/// Verify compressed proofs of the guest program in the aggregation program.
+///
+/// The proofs are written to the stdin after the [`AggregationInput`], the
+/// aggregation program verifies them with `verify_sp1_proof`.
+pub fn aggregate<P>(
+    client: P,
+    proofs: Vec<ProofOutput>,
+    guest_program: &[u8],
+    aggregation_program: &[u8],
+    commitment: AggregationCommitment,
+    mode: ProofMode,
+) -> Result<ProofOutput>
+where
+    P: Prover<CpuProverComponents>,
+{
+    let (_, vk) = client.setup(guest_program);
+    let program = vk.bytes32().parse()?;
+
+    let input = AggregationInput {
+        program: vk.hash_u32(),
+        public_outputs: proofs.iter().map(|p| p.public_output.clone()).collect(),
+        commitment,
+    };
+
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&input);
+
+    for (i, proof) in proofs.into_iter().enumerate() {
+        if proof.metadata.program != program {
+            return Err(anyhow!("proof #{} is not a proof of the program", i));
+        }
+
+        let encoded = proof
+            .proof
+            .ok_or_else(|| anyhow!("proof #{} is a mock proof, it can't be aggregated", i))?;
+        let proof: SP1ProofWithPublicValues = bincode::deserialize(&encoded)?;
+
+        let SP1Proof::Compressed(proof) = proof.proof else {
+            return Err(anyhow!(
+                "proof #{} is not a compressed proof, only those can be aggregated",
+                i
+            ));
+        };
+        stdin.write_proof(*proof, vk.vk.clone());
+    }
+
+    let output = prove_stdin(client, stdin, aggregation_program, mode)?;
+
+    if output.public_output != input.expected_output() {
+        return Err(anyhow!(
+            "aggregation program committed an unexpected output, it doesn't follow the aggregation input"
+        ));
+    }
+
+    Ok(output)
+}
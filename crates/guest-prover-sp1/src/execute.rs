@@ -0,0 +1,56 @@
+use std::future::Future;
+
+use anyhow::Result;
+use sp1_sdk::ProverClient;
+use sp1_stark::SP1CoreOpts;
+use zktls_core::{spawn_prover, Backend, ExecutionOutput, ProveError, ZkExecutor};
+use zktls_program_core::GuestInput;
+
+use crate::{guest_stdin, SP1GuestProver};
+
+impl ZkExecutor for SP1GuestProver {
+    fn execute(
+        &mut self,
+        input: GuestInput,
+        guest_program: &[u8],
+    ) -> impl Future<Output = Result<ExecutionOutput>> + Send {
+        let guest_program = guest_program.to_vec();
+
+        spawn_prover(self.deadline, move || execute(input, &guest_program))
+    }
+}
+
+/// Execute the guest program with the SP1 executor.
+///
+/// The panic message of a rejected input is printed by the executor.
+pub fn execute(input: GuestInput, guest_program: &[u8]) -> Result<ExecutionOutput> {
+    let stdin = guest_stdin(&input)?;
+
+    let client = ProverClient::builder().cpu().build();
+    let (public_values, report) = client
+        .execute(guest_program, &stdin)
+        .run()
+        .map_err(|e| ProveError::GuestRejected(e.to_string()))?;
+
+    let cycles = report.total_instruction_count();
+    // The prover cuts the execution in shards of `shard_size` cycles, which
+    // honours `SHARD_SIZE` like the prover does.
+    let shard_size = SP1CoreOpts::default().shard_size as u64;
+    let shards = cycles.div_ceil(shard_size).max(1);
+    log::info!(
+        "executed {} cycles in {} shards, {} syscalls",
+        cycles,
+        shards,
+        report.total_syscall_count()
+    );
+
+    // The report only counts instructions, there is no padding to tell apart.
+    Ok(ExecutionOutput {
+        backend: Backend::Sp1,
+        public_output: public_values.to_vec(),
+        total_cycles: cycles,
+        user_cycles: None,
+        segments: shards,
+        cycle_tracker: report.cycle_tracker.into_iter().collect(),
+    })
+}
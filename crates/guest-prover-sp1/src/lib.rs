@@ -1,2 +1,14 @@
 mod sp1;
 pub use sp1::*;
+
+mod program;
+pub use program::*;
+
+mod verify;
+pub use verify::*;
+
+mod execute;
+pub use execute::*;
+
+mod aggregate;
+pub use aggregate::*;
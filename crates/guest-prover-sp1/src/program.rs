@@ -0,0 +1,18 @@
+use std::path::PathBuf;
+
+use sp1_sdk::{HashableKey, Prover, ProverClient};
+
+/// Verification key hash of the guest program, as used by the SP1 verifier contracts.
+pub fn program_vkey(guest_program: &[u8]) -> String {
+    let client = ProverClient::builder().cpu().build();
+    let (_, vk) = client.setup(guest_program);
+
+    vk.bytes32()
+}
+
+/// Directory of the Groth16 circuit artifacts, downloaded if missing.
+///
+/// It contains the Solidity verifier of the installed SP1 version.
+pub fn groth16_artifacts_dir() -> PathBuf {
+    sp1_sdk::install::try_install_circuit_artifacts("groth16")
+}
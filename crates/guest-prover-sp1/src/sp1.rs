@@ -1,13 +1,17 @@
-use std::{future::Future, panic};
+use std::{future::Future, time::Duration};
 
 use alloy_primitives::hex;
 use anyhow::Result;
 use sp1_prover::components::CpuProverComponents;
-use sp1_sdk::{Prover, ProverClient, SP1Stdin};
-use zktls_core::ZkProver;
+use sp1_sdk::{
+    HashableKey, Prover, ProverClient, SP1Proof, SP1ProofMode, SP1ProofWithPublicValues, SP1Stdin,
+};
+use zktls_core::{
+    spawn_prover, Backend, ProofMetadata, ProofMode, ProofOutput, ProofSystem, ZkProver,
+};
 use zktls_program_core::GuestInput;
 
-#[derive(Default)]
+#[derive(Debug, Clone, Copy, Default)]
 pub enum ProverType {
     #[default]
     Mock,
@@ -17,21 +21,10 @@ pub enum ProverType {
     Network,
 }
 
-impl ProverType {
-    pub fn set_env(&self) {
-        match self {
-            ProverType::Mock => std::env::set_var("SP1_PROVER", "mock"),
-            ProverType::Local => std::env::set_var("SP1_PROVER", "local"),
-            #[cfg(feature = "cuda")]
-            ProverType::Cuda => std::env::set_var("SP1_PROVER", "cuda"),
-            ProverType::Network => std::env::set_var("SP1_PROVER", "network"),
-        }
-    }
-}
-
 pub struct SP1GuestProver {
-    mode: ProverType,
-    moongate_server: Option<String>,
+    pub(crate) mode: ProverType,
+    pub(crate) moongate_server: Option<String>,
+    pub(crate) deadline: Option<Duration>,
 }
 
 impl SP1GuestProver {
@@ -39,9 +32,16 @@ impl SP1GuestProver {
         Self {
             mode: ProverType::default(),
             moongate_server,
+            deadline: None,
         }
     }
 
+    /// Fail proofs and executions which take longer than `deadline`.
+    pub fn deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
     pub fn mock(mut self) -> Self {
         self.mode = ProverType::Mock;
         self
@@ -68,66 +68,162 @@ impl ZkProver for SP1GuestProver {
         &mut self,
         input: GuestInput,
         guest_program: &[u8],
-    ) -> impl Future<Output = Result<(Vec<u8>, Vec<u8>)>> + Send {
-        self.mode.set_env();
-
+        mode: ProofMode,
+    ) -> impl Future<Output = Result<ProofOutput>> + Send {
         let guest_program = guest_program.to_vec();
-
-        _panic_catched_prove(input, guest_program, &self.moongate_server)
+        let prover_type = self.mode;
+        let moongate_server = self.moongate_server.clone();
+
+        spawn_prover(self.deadline, move || {
+            with_client(
+                prover_type,
+                moongate_server,
+                ProveTask {
+                    input,
+                    guest_program,
+                    mode,
+                },
+            )
+        })
     }
 }
 
-async fn _panic_catched_prove(
+/// Work done with the client of a prover type, see [`with_client`].
+pub(crate) trait ClientTask {
+    type Output;
+
+    fn run<P: Prover<CpuProverComponents>>(self, client: P) -> Result<Self::Output>;
+}
+
+struct ProveTask {
     input: GuestInput,
     guest_program: Vec<u8>,
-    moongate_server: &Option<String>,
-) -> Result<(Vec<u8>, Vec<u8>)> {
-    panic::catch_unwind(move || {
-        if let Some(server) = moongate_server {
-            let prover = ProverClient::builder()
-                .cuda()
-                .with_moongate_endpoint(server)
-                .build();
-
-            prove(prover, input, &guest_program)
-        } else {
-            let client = ProverClient::from_env();
-
-            prove(client, input, &guest_program)
-        }
-    })
-    .map_err(|e| anyhow::anyhow!("{:?}", e))?
+    mode: ProofMode,
 }
 
-pub fn prove<P>(client: P, input: GuestInput, guest_program: &[u8]) -> Result<(Vec<u8>, Vec<u8>)>
+impl ClientTask for ProveTask {
+    type Output = ProofOutput;
+
+    fn run<P: Prover<CpuProverComponents>>(self, client: P) -> Result<ProofOutput> {
+        prove(client, self.input, &self.guest_program, self.mode)
+    }
+}
+
+/// Build the client of the prover type and run the task with it, ignoring `SP1_PROVER`.
+///
+/// The network prover reads its private key from `NETWORK_PRIVATE_KEY`.
+pub(crate) fn with_client<T: ClientTask>(
+    prover_type: ProverType,
+    moongate_server: Option<String>,
+    task: T,
+) -> Result<T::Output> {
+    if let Some(server) = moongate_server {
+        let prover = ProverClient::builder()
+            .cuda()
+            .with_moongate_endpoint(&server)
+            .build();
+
+        return task.run(prover);
+    }
+
+    match prover_type {
+        ProverType::Mock => task.run(ProverClient::builder().mock().build()),
+        ProverType::Local => task.run(ProverClient::builder().cpu().build()),
+        #[cfg(feature = "cuda")]
+        ProverType::Cuda => task.run(ProverClient::builder().cuda().build()),
+        ProverType::Network => task.run(ProverClient::builder().network().build()),
+    }
+}
+
+pub fn prove<P>(
+    client: P,
+    input: GuestInput,
+    guest_program: &[u8],
+    mode: ProofMode,
+) -> Result<ProofOutput>
 where
     P: Prover<CpuProverComponents>,
 {
-    let mut stdin = SP1Stdin::new();
-
-    let mut input_bytes = Vec::new();
-    ciborium::into_writer(&input, &mut input_bytes)?;
-
-    stdin.write_vec(input_bytes);
+    prove_stdin(client, guest_stdin(&input)?, guest_program, mode)
+}
 
+/// Prove the program on `stdin`, and check the proof.
+pub(crate) fn prove_stdin<P>(
+    client: P,
+    stdin: SP1Stdin,
+    guest_program: &[u8],
+    mode: ProofMode,
+) -> Result<ProofOutput>
+where
+    P: Prover<CpuProverComponents>,
+{
     let (pk, vk) = client.setup(guest_program);
 
     let start = std::time::Instant::now();
-    let prover_output = client.prove(&pk, &stdin, sp1_sdk::SP1ProofMode::Groth16)?;
+    let prover_output = client.prove(&pk, &stdin, sp1_proof_mode(mode))?;
     let elapsed = start.elapsed();
     log::info!("Proving time: {:?}", elapsed);
 
     client.verify(&prover_output, &vk)?;
 
     let output = prover_output.public_values.to_vec();
-    let mut proof = prover_output.bytes();
-
     log::info!("output: {}", hex::encode(&output));
-    log::info!("proof: {}", hex::encode(&proof));
 
-    if proof.len() <= 4 {
-        proof = Vec::new();
+    let proof = encode_proof(&prover_output)?;
+    if let Some(proof) = &proof {
+        log::info!("proof: {}", hex::encode(proof));
     }
+    let proof_system = match proof {
+        Some(_) => mode.into(),
+        None => ProofSystem::Mock,
+    };
+
+    Ok(ProofOutput {
+        public_output: output,
+        proof,
+        proof_system,
+        metadata: ProofMetadata {
+            backend: Backend::Sp1,
+            program: vk.bytes32().parse()?,
+            proving_time: elapsed,
+        },
+    })
+}
 
-    Ok((output, proof))
+pub(crate) fn guest_stdin(input: &GuestInput) -> Result<SP1Stdin> {
+    let mut input_bytes = Vec::new();
+    ciborium::into_writer(input, &mut input_bytes)?;
+
+    let mut stdin = SP1Stdin::new();
+    stdin.write_vec(input_bytes);
+
+    Ok(stdin)
+}
+
+fn sp1_proof_mode(mode: ProofMode) -> SP1ProofMode {
+    match mode {
+        ProofMode::Core => SP1ProofMode::Core,
+        ProofMode::Compressed => SP1ProofMode::Compressed,
+        ProofMode::Groth16 => SP1ProofMode::Groth16,
+        ProofMode::Plonk => SP1ProofMode::Plonk,
+    }
+}
+
+/// Encode the proof, `None` for the empty proofs of the mock prover.
+fn encode_proof(proof: &SP1ProofWithPublicValues) -> Result<Option<Vec<u8>>> {
+    let is_mock = match &proof.proof {
+        SP1Proof::Core(shards) => shards.is_empty(),
+        SP1Proof::Compressed(_) => false,
+        SP1Proof::Groth16(p) => p.encoded_proof.is_empty(),
+        SP1Proof::Plonk(p) => p.encoded_proof.is_empty(),
+    };
+
+    if is_mock {
+        return Ok(None);
+    }
+
+    match &proof.proof {
+        SP1Proof::Groth16(_) | SP1Proof::Plonk(_) => Ok(Some(proof.bytes())),
+        _ => Ok(Some(bincode::serialize(proof)?)),
+    }
 }
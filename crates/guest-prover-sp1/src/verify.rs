@@ -0,0 +1,49 @@
+use anyhow::{anyhow, Result};
+use sp1_sdk::{Prover, ProverClient, SP1ProofWithPublicValues};
+use sp1_verifier::{Groth16Verifier, PlonkVerifier, GROTH16_VK_BYTES, PLONK_VK_BYTES};
+use zktls_core::ProofSystem;
+
+use crate::program_vkey;
+
+/// Verify a proof of the guest program against its public values.
+///
+/// The proof is the one returned by the prover: prefixed with the verifier hash
+/// for Groth16 and PLONK, or for core and compressed proofs the
+/// `SP1ProofWithPublicValues` encoded with bincode 1 and its default options.
+pub fn verify(
+    guest_program: &[u8],
+    output: &[u8],
+    proof: &[u8],
+    proof_system: ProofSystem,
+) -> Result<()> {
+    if proof.is_empty() || proof_system == ProofSystem::Mock {
+        return Err(anyhow!("empty proof, mock proofs can't be verified"));
+    }
+
+    match proof_system {
+        ProofSystem::Groth16 => Groth16Verifier::verify(
+            proof,
+            output,
+            &program_vkey(guest_program),
+            &GROTH16_VK_BYTES,
+        )
+        .map_err(|e| anyhow!("invalid proof: {}", e)),
+        ProofSystem::Plonk => {
+            PlonkVerifier::verify(proof, output, &program_vkey(guest_program), &PLONK_VK_BYTES)
+                .map_err(|e| anyhow!("invalid proof: {}", e))
+        }
+        _ => {
+            let proof: SP1ProofWithPublicValues = bincode::deserialize(proof)?;
+            if proof.public_values.as_slice() != output {
+                return Err(anyhow!("public values of the proof don't match the output"));
+            }
+
+            let client = ProverClient::builder().cpu().build();
+            let (_, vk) = client.setup(guest_program);
+
+            client
+                .verify(&proof, &vk)
+                .map_err(|e| anyhow!("invalid proof: {}", e))
+        }
+    }
+}
@@ -1 +1,97 @@
+use std::{num::NonZeroUsize, path::PathBuf, time::Duration};
 
+use alloy_primitives::B256;
+use rustls::pki_types::CertificateDer;
+
+use crate::RootSource;
+
+/// Configuration of the [`TLSInputBuilder`](crate::TLSInputBuilder).
+#[derive(Debug, Clone)]
+pub struct TLSInputBuilderConfig {
+    /// Maximum number of compiled regex patterns kept in the cache.
+    pub regex_cache_size: NonZeroUsize,
+    /// Save the [`Transcript`](crate::Transcript) of every TLS call to this file.
+    pub record_transcript: Option<PathBuf>,
+    /// Sources of the root certificates trusted by TLS calls.
+    pub roots: Vec<RootSource>,
+    /// Only trust the roots with these fingerprints, see [`root_fingerprint`](crate::root_fingerprint).
+    ///
+    /// The allowlist only restricts the TLS calls of the host, it isn't part of
+    /// the [`GuestInput`](zktls_program_core::GuestInput). The root used by a
+    /// call is reported by [`TLSInputBuilder::trusted_root`](crate::TLSInputBuilder::trusted_root).
+    pub allowed_roots: Vec<B256>,
+    /// Timeouts and size limit of TLS calls.
+    pub limits: RequestLimits,
+}
+
+impl Default for TLSInputBuilderConfig {
+    fn default() -> Self {
+        Self {
+            regex_cache_size: NonZeroUsize::new(64).expect("64 is not zero"),
+            record_transcript: None,
+            roots: vec![RootSource::WebPki],
+            allowed_roots: Vec::new(),
+            limits: RequestLimits::default(),
+        }
+    }
+}
+
+impl TLSInputBuilderConfig {
+    pub fn regex_cache_size(mut self, size: NonZeroUsize) -> Self {
+        self.regex_cache_size = size;
+        self
+    }
+
+    pub fn record_transcript(mut self, path: impl Into<PathBuf>) -> Self {
+        self.record_transcript = Some(path.into());
+        self
+    }
+
+    pub fn roots(mut self, roots: Vec<RootSource>) -> Self {
+        self.roots = roots;
+        self
+    }
+
+    pub fn add_root_source(mut self, source: RootSource) -> Self {
+        self.roots.push(source);
+        self
+    }
+
+    pub fn add_root(self, certificate: CertificateDer<'static>) -> Self {
+        self.add_root_source(RootSource::Certificate(certificate))
+    }
+
+    pub fn allow_root(mut self, fingerprint: B256) -> Self {
+        self.allowed_roots.push(fingerprint);
+        self
+    }
+
+    pub fn limits(mut self, limits: RequestLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+/// Limits of a TLS call, so a slow or malicious server can't hold a worker forever.
+#[derive(Debug, Clone)]
+pub struct RequestLimits {
+    /// Timeout of the TCP connect.
+    pub connect_timeout: Duration,
+    /// Timeout of the TLS handshake.
+    pub handshake_timeout: Duration,
+    /// Maximum time without receiving data while sending the request and reading the response.
+    pub read_timeout: Duration,
+    /// Maximum size of the response.
+    pub max_response_size: usize,
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(10),
+            handshake_timeout: Duration::from_secs(10),
+            read_timeout: Duration::from_secs(30),
+            max_response_size: 16 * 1024 * 1024,
+        }
+    }
+}
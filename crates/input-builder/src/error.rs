@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use crate::{HttpError, JsonPathError};
+
+/// Error of a single response template.
+#[derive(Debug, thiserror::Error)]
+pub enum TemplateError {
+    #[error(
+        "range {begin}..{begin}+{length} is out of bounds of a {response_length} bytes response"
+    )]
+    OutOfBounds {
+        begin: u64,
+        length: u64,
+        response_length: usize,
+    },
+    #[error("range {begin}..{begin}+{length} overflows")]
+    Overflow { begin: u64, length: u64 },
+    #[error("no match for {0}")]
+    NoMatch(String),
+    #[error("no terminator found for the value at {begin}")]
+    Unterminated { begin: u64 },
+    #[error("invalid template: {0}")]
+    InvalidTemplate(&'static str),
+    #[error("invalid HTTP response: {0}")]
+    Http(#[from] HttpError),
+    #[error("{0}")]
+    JsonPath(#[from] JsonPathError),
+    #[error("header {0} not found")]
+    MissingHeader(String),
+    #[error("range {begin}..{begin}+{length} of the body crosses a chunk boundary")]
+    SpansChunks { begin: u64, length: u64 },
+    #[error("invalid regex pattern: {0}")]
+    InvalidPattern(#[from] regex::Error),
+}
+
+/// Response template failed while building the input.
+///
+/// `index` counts the templates of [`Request::response_template`] first, then
+/// the extra templates of the [`TemplatedRequest`].
+///
+/// [`Request::response_template`]: zktls_program_core::Request
+/// [`TemplatedRequest`]: crate::TemplatedRequest
+#[derive(Debug, thiserror::Error)]
+#[error("response template #{index} ({kind}) failed: {error}")]
+pub struct TemplateFailure {
+    pub index: usize,
+    pub kind: &'static str,
+    #[source]
+    pub error: TemplateError,
+}
+
+/// Error of the TLS call to the remote server.
+#[derive(Debug, thiserror::Error)]
+pub enum TlsCallError {
+    #[error("connect timed out after {0:?}")]
+    ConnectTimeout(Duration),
+    #[error("TLS handshake timed out after {0:?}")]
+    HandshakeTimeout(Duration),
+    #[error("no data received from the server for {0:?}")]
+    ReadTimeout(Duration),
+    #[error("response is larger than {0} bytes")]
+    ResponseTooLarge(usize),
+    #[error("TLS call cancelled")]
+    Cancelled,
+}
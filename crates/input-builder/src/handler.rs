@@ -1,67 +1,152 @@
+use std::{
+    borrow::Cow,
+    ops::Range,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use alloy_primitives::B256;
 use anyhow::Result;
 use memchr::memmem::Finder;
+use rustls::RootCertStore;
 use zktls_core::InputBuilder;
-use zktls_program_core::{GuestInput, Request, ResponseTemplate};
+use zktls_program_core::{GuestInput, GuestInputResponse, Request};
 
-use crate::{request::request_tls_call, FilteredResponse};
+use crate::{
+    build_root_store, find_json_path,
+    http::map_range,
+    request::{request_tls_call, TlsCallResponse},
+    root_fingerprint, FilteredResponse, HttpResponse, MatchSelect, RegexCache, RequestLimits,
+    ResponseTarget, TLSInputBuilderConfig, Template, TemplateError, TemplateFailure, TemplateKind,
+    TemplatePolicy, TemplatedRequest, Transcript,
+};
 
-pub struct TLSInputBuilder {}
+pub struct TLSInputBuilder {
+    regex_cache: RegexCache,
+    record_transcript: Option<PathBuf>,
+    root_store: Arc<RootCertStore>,
+    limits: RequestLimits,
+    trusted_root: Option<B256>,
+}
 
 impl TLSInputBuilder {
     pub fn new() -> Result<Self> {
-        Ok(Self {})
+        Self::with_config(TLSInputBuilderConfig::default())
+    }
+
+    pub fn with_config(config: TLSInputBuilderConfig) -> Result<Self> {
+        let root_store = build_root_store(&config.roots, &config.allowed_roots)?;
+
+        Ok(Self {
+            regex_cache: RegexCache::new(config.regex_cache_size),
+            record_transcript: config.record_transcript,
+            root_store: Arc::new(root_store),
+            limits: config.limits,
+            trusted_root: None,
+        })
+    }
+
+    /// Fingerprints of the trusted roots, see [`root_fingerprint`].
+    pub fn trust_anchors(&self) -> Vec<B256> {
+        self.root_store.roots.iter().map(root_fingerprint).collect()
+    }
+
+    /// Fingerprint of the root which anchored the certificate chain of the
+    /// server for the last input built, see [`chain_root`](crate::chain_root).
+    /// Replayed inputs take the root recorded in their transcript.
+    ///
+    /// `GuestInput` has no field for it, so the proof artifact records it.
+    pub fn trusted_root(&self) -> Option<B256> {
+        self.trusted_root
+    }
+
+    /// Build the input with templates which are not part of [`Request`].
+    pub async fn build_templated_input(&mut self, request: TemplatedRequest) -> Result<GuestInput> {
+        self.handle_request_tls_call(request).await
+    }
+
+    /// Build the input from a recorded [`Transcript`] instead of calling the server.
+    pub fn build_replayed_input(
+        &mut self,
+        request: TemplatedRequest,
+        transcript: Transcript,
+    ) -> Result<GuestInput> {
+        transcript.check_request(&request.request)?;
+
+        self.trusted_root = transcript.root;
+        self.handle_response(request, transcript.response)
     }
 }
 
 impl InputBuilder for TLSInputBuilder {
     async fn build_input(&mut self, request: Request) -> Result<GuestInput> {
-        self.handle_request_tls_call(request).await
+        self.handle_request_tls_call(request.into()).await
     }
 }
 
 impl TLSInputBuilder {
-    async fn handle_request_tls_call(&mut self, req: Request) -> Result<GuestInput> {
+    async fn handle_request_tls_call(&mut self, req: TemplatedRequest) -> Result<GuestInput> {
         // OPT: avoid cloning
-        let req_cloned = req.clone();
-
-        let mut guest_input_response =
-            tokio::task::spawn_blocking(move || request_tls_call(&req_cloned)).await??;
-
-        for template in &req.response_template {
-            match template {
-                ResponseTemplate::Offset { begin, length } => {
-                    let fr = self.handle_response_template_position(
-                        *begin,
-                        *length,
-                        &guest_input_response.response,
-                    )?;
-
-                    guest_input_response.filtered_responses_begin.push(fr.begin);
-                    guest_input_response
-                        .filtered_responses_length
-                        .push(fr.length);
-                    guest_input_response
-                        .filtered_responses
-                        .push(fr.bytes.into());
-                }
-                ResponseTemplate::Prefix { prefix, length } => {
-                    let fr = self.handle_response_template_prefix(
-                        prefix,
-                        *length,
-                        &guest_input_response.response,
-                    )?;
-
-                    guest_input_response
-                        .filtered_responses_begin
-                        .extend(fr.iter().map(|fr| fr.begin));
-                    guest_input_response
-                        .filtered_responses_length
-                        .extend(fr.iter().map(|fr| fr.length));
-                    guest_input_response
-                        .filtered_responses
-                        .extend(fr.into_iter().map(|fr| fr.bytes.into()));
-                }
-            }
+        let req_cloned = req.request.clone();
+        let root_store = self.root_store.clone();
+        let limits = self.limits.clone();
+
+        // Stop the TLS call if this future is dropped.
+        let cancel = CancelOnDrop::default();
+        let cancel_flag = cancel.0.clone();
+
+        self.trusted_root = None;
+        let TlsCallResponse { response, root } = tokio::task::spawn_blocking(move || {
+            request_tls_call(&req_cloned, root_store, &limits, &cancel_flag)
+        })
+        .await??;
+        self.trusted_root = root;
+
+        if let Some(path) = &self.record_transcript {
+            Transcript::new(req.request.clone(), response.clone(), root).save(path)?;
+            log::info!("Recorded transcript to {}", path.display());
+        }
+
+        self.handle_response(req, response)
+    }
+
+    fn handle_response(
+        &mut self,
+        req: TemplatedRequest,
+        mut guest_input_response: GuestInputResponse,
+    ) -> Result<GuestInput> {
+        let TemplatedRequest {
+            request: req,
+            templates,
+        } = req;
+
+        let templates = req
+            .response_template
+            .iter()
+            .map(Template::from)
+            .chain(templates);
+
+        for (index, template) in templates.enumerate() {
+            let fr = self
+                .handle_response_template(&template, &guest_input_response.response)
+                .map_err(|error| TemplateFailure {
+                    index,
+                    kind: template.kind.name(),
+                    error,
+                })?;
+
+            guest_input_response
+                .filtered_responses_begin
+                .extend(fr.iter().map(|fr| fr.begin));
+            guest_input_response
+                .filtered_responses_length
+                .extend(fr.iter().map(|fr| fr.length));
+            guest_input_response
+                .filtered_responses
+                .extend(fr.into_iter().map(|fr| fr.bytes.into()));
         }
 
         Ok(GuestInput {
@@ -70,82 +155,640 @@ impl TLSInputBuilder {
         })
     }
 
+    fn handle_response_template(
+        &mut self,
+        template: &Template,
+        response: &[u8],
+    ) -> Result<Vec<FilteredResponse>, TemplateError> {
+        let policy = template.policy;
+
+        let target = match (&template.kind, &template.target) {
+            (TemplateKind::JsonPath { .. }, ResponseTarget::Raw) => &ResponseTarget::Body,
+            (_, target) => target,
+        };
+
+        let view = target_view(target, response)?;
+        let target = view.bytes.as_ref();
+
+        let res: Vec<FilteredResponse> = match &template.kind {
+            TemplateKind::Offset { begin, length } => self
+                .handle_response_template_position(*begin, *length, target, policy)?
+                .into_iter()
+                .collect(),
+            TemplateKind::Prefix {
+                prefix,
+                length,
+                terminator,
+                select,
+            } => self.handle_response_template_prefix(
+                prefix,
+                *length,
+                terminator.as_ref().map(|t| t.as_ref()),
+                *select,
+                target,
+                policy,
+            )?,
+            TemplateKind::Regex { pattern, select } => {
+                select.apply(self.regex_cache.find(pattern, target)?.into_iter())
+            }
+            TemplateKind::JsonPath { path } => find_json_path(target, path)?
+                .map(|range| FilteredResponse::new(target, range))
+                .into_iter()
+                .collect(),
+        };
+
+        if res.is_empty() && policy == TemplatePolicy::Fail {
+            return Err(TemplateError::NoMatch(format!("{:?}", template.kind)));
+        }
+
+        res.into_iter()
+            .map(|fr| {
+                let range = fr.begin as usize..(fr.begin + fr.length) as usize;
+
+                map_range(&view.pieces, range)
+                    .map(|range| FilteredResponse::new(response, range))
+                    .ok_or(TemplateError::SpansChunks {
+                        begin: fr.begin,
+                        length: fr.length,
+                    })
+            })
+            .collect()
+    }
+
     fn handle_response_template_position(
         &mut self,
         begin: u64,
         length: u64,
         s: &[u8],
-    ) -> Result<FilteredResponse> {
-        let content = s[begin as usize..(begin + length) as usize].to_vec();
+        policy: TemplatePolicy,
+    ) -> Result<Option<FilteredResponse>, TemplateError> {
+        let range = checked_range(begin, length, s.len(), policy)?;
 
-        Ok(FilteredResponse {
-            begin,
-            length,
-            bytes: content,
-        })
+        Ok(range.map(|range| FilteredResponse::new(s, range)))
     }
 
     fn handle_response_template_prefix(
         &mut self,
         prefix: &[u8],
-        length: u64,
+        length: Option<u64>,
+        terminator: Option<&[u8]>,
+        select: MatchSelect,
         response: &[u8],
-    ) -> Result<Vec<FilteredResponse>> {
-        let finder = Finder::new(response);
-        let filtered_responses_iter = finder.find_iter(response);
+        policy: TemplatePolicy,
+    ) -> Result<Vec<FilteredResponse>, TemplateError> {
+        if prefix.is_empty() {
+            return Err(TemplateError::InvalidTemplate("prefix is empty"));
+        }
+
+        let finder = Finder::new(prefix);
+        let filtered_responses_iter = select.apply(finder.find_iter(response));
 
         let mut res = Vec::new();
 
         for m in filtered_responses_iter {
             let begin = m + prefix.len();
-            let end = begin + length as usize;
-
-            let content = response[begin as usize..end as usize].to_vec();
 
-            let filtered_response = FilteredResponse {
-                begin: begin as u64,
-                length,
-                bytes: content,
+            let range = match (terminator, length) {
+                (Some(terminator), _) => {
+                    terminated_range(begin, length, terminator, response, policy)?
+                }
+                (None, Some(length)) => {
+                    checked_range(begin as u64, length, response.len(), policy)?
+                }
+                (None, None) => {
+                    return Err(TemplateError::InvalidTemplate(
+                        "prefix template needs a length or a terminator",
+                    ))
+                }
             };
 
-            res.push(filtered_response);
+            if let Some(range) = range {
+                res.push(FilteredResponse::new(response, range));
+            }
         }
 
         Ok(res)
     }
 }
 
+/// Find the value starting at `begin` and ending before `terminator`, applying
+/// `policy` when the terminator isn't found within `max_length` bytes.
+fn terminated_range(
+    begin: usize,
+    max_length: Option<u64>,
+    terminator: &[u8],
+    response: &[u8],
+    policy: TemplatePolicy,
+) -> Result<Option<Range<usize>>, TemplateError> {
+    if terminator.is_empty() {
+        return Err(TemplateError::InvalidTemplate("terminator is empty"));
+    }
+
+    let rest = &response[begin..];
+    let max_length = max_length
+        .and_then(|l| usize::try_from(l).ok())
+        .map_or(rest.len(), |l| l.min(rest.len()));
+
+    match memchr::memmem::find(rest, terminator) {
+        Some(end) if end <= max_length => Ok(Some(begin..begin + end)),
+        _ => match policy {
+            TemplatePolicy::Fail => Err(TemplateError::Unterminated {
+                begin: begin as u64,
+            }),
+            TemplatePolicy::Skip => Ok(None),
+            TemplatePolicy::Truncate => Ok(Some(begin..begin + max_length)),
+        },
+    }
+}
+
+/// Bytes of the response targeted by a template.
+struct TargetView<'a> {
+    bytes: Cow<'a, [u8]>,
+    /// Pieces of the raw response the bytes come from.
+    pieces: Vec<Range<usize>>,
+}
+
+fn target_view<'a>(
+    target: &ResponseTarget,
+    response: &'a [u8],
+) -> Result<TargetView<'a>, TemplateError> {
+    if *target == ResponseTarget::Raw {
+        let whole = 0..response.len();
+        return Ok(TargetView {
+            bytes: Cow::Borrowed(response),
+            pieces: vec![whole],
+        });
+    }
+
+    let http = HttpResponse::parse(response)?;
+
+    let range = match target {
+        ResponseTarget::Raw => unreachable!("raw target is handled above"),
+        ResponseTarget::Status => http.status.clone(),
+        ResponseTarget::Header(name) => http
+            .header(response, name)
+            .ok_or_else(|| TemplateError::MissingHeader(name.clone()))?,
+        ResponseTarget::Body => {
+            return Ok(TargetView {
+                bytes: http.body(response),
+                pieces: http.body,
+            })
+        }
+    };
+
+    Ok(TargetView {
+        bytes: Cow::Borrowed(&response[range.clone()]),
+        pieces: vec![range],
+    })
+}
+
+#[derive(Default)]
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Check `begin..begin + length` against the response length, applying `policy`
+/// when the range doesn't fit.
+fn checked_range(
+    begin: u64,
+    length: u64,
+    response_length: usize,
+    policy: TemplatePolicy,
+) -> Result<Option<Range<usize>>, TemplateError> {
+    let end = begin
+        .checked_add(length)
+        .ok_or(TemplateError::Overflow { begin, length });
+
+    let end = match (end, policy) {
+        (Ok(end), _) => end,
+        (Err(_), TemplatePolicy::Truncate) => u64::MAX,
+        (Err(e), _) => return Err(e),
+    };
+
+    if end <= response_length as u64 {
+        return Ok(Some(begin as usize..end as usize));
+    }
+
+    match policy {
+        TemplatePolicy::Fail => Err(TemplateError::OutOfBounds {
+            begin,
+            length,
+            response_length,
+        }),
+        TemplatePolicy::Skip => Ok(None),
+        TemplatePolicy::Truncate if begin < response_length as u64 => {
+            Ok(Some(begin as usize..response_length))
+        }
+        TemplatePolicy::Truncate => Ok(None),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
+    use std::{env, fs, net::TcpListener, time::Duration};
 
     use zktls_program_core::Request;
+    use zktls_tls_fixture::FixtureServer;
 
-    use crate::TLSInputBuilder;
+    use crate::{
+        build_root_store, root_fingerprint, MatchSelect, RequestLimits, ResponseTarget, RootSource,
+        TLSInputBuilder, TLSInputBuilderConfig, TemplateError, TemplateKind, TemplatePolicy,
+        TemplatedRequest, TlsCallError, Transcript,
+    };
 
-    #[tokio::test]
-    async fn test_handle_response1() {
-        // TODO: We need a new test data
+    #[test]
+    fn test_handle_response_template_regex() {
+        let response = b"HTTP/1.1 200 OK\r\n\r\n{\"price\": \"1.25\", \"price\":\"3\"}";
+
+        let mut builder = TLSInputBuilder::new().unwrap();
+
+        let template = TemplateKind::Regex {
+            pattern: r#""price":\s*"[0-9.]+""#.into(),
+            select: MatchSelect::All,
+        }
+        .into();
+
+        let fr = builder
+            .handle_response_template(&template, response)
+            .unwrap();
+
+        assert_eq!(fr.len(), 2);
+        assert_eq!(fr[0].bytes, br#""price": "1.25""#);
+        assert_eq!(
+            &response[fr[1].begin as usize..(fr[1].begin + fr[1].length) as usize],
+            br#""price":"3""#
+        );
+    }
+
+    #[test]
+    fn test_handle_response_template_policy() {
+        let response = b"HTTP/1.1 200 OK\r\n\r\nhello";
+
+        let mut builder = TLSInputBuilder::new().unwrap();
+
+        let mut template = crate::Template::from(TemplateKind::Offset {
+            begin: 19,
+            length: 10,
+        });
+
+        let err = builder
+            .handle_response_template(&template, response)
+            .unwrap_err();
+        assert!(matches!(err, TemplateError::OutOfBounds { .. }));
+
+        template.policy = TemplatePolicy::Skip;
+        let fr = builder
+            .handle_response_template(&template, response)
+            .unwrap();
+        assert!(fr.is_empty());
+
+        template.policy = TemplatePolicy::Truncate;
+        let fr = builder
+            .handle_response_template(&template, response)
+            .unwrap();
+        assert_eq!(fr[0].bytes, b"hello");
+        assert_eq!(fr[0].length, 5);
+
+        template.kind = TemplateKind::Offset {
+            begin: u64::MAX,
+            length: 1,
+        };
+        template.policy = TemplatePolicy::Fail;
+        let err = builder
+            .handle_response_template(&template, response)
+            .unwrap_err();
+        assert!(matches!(err, TemplateError::Overflow { .. }));
+    }
+
+    const RESPONSE0: &[u8] = include_bytes!("../testdata/response0.http");
+
+    fn prefix_template(
+        prefix: &[u8],
+        length: Option<u64>,
+        terminator: Option<&[u8]>,
+        select: MatchSelect,
+    ) -> crate::Template {
+        TemplateKind::Prefix {
+            prefix: prefix.to_vec().into(),
+            length,
+            terminator: terminator.map(|t| t.to_vec().into()),
+            select,
+        }
+        .into()
+    }
+
+    fn values(fr: &[crate::FilteredResponse]) -> Vec<&[u8]> {
+        fr.iter()
+            .map(|fr| &RESPONSE0[fr.begin as usize..(fr.begin + fr.length) as usize])
+            .collect()
+    }
+
+    #[test]
+    fn test_handle_response_template_prefix() {
+        let mut builder = TLSInputBuilder::new().unwrap();
+
+        let template = prefix_template(b"\"symbol\":\"", Some(3), None, MatchSelect::All);
+        let fr = builder
+            .handle_response_template(&template, RESPONSE0)
+            .unwrap();
+        assert_eq!(values(&fr), [b"BTC", b"ETH", b"SOL"]);
+
+        let template = prefix_template(b"\"symbol\":\"", Some(3), None, MatchSelect::First);
+        let fr = builder
+            .handle_response_template(&template, RESPONSE0)
+            .unwrap();
+        assert_eq!(values(&fr), [b"BTC"]);
+
+        let template = prefix_template(b"\"symbol\":\"", Some(3), None, MatchSelect::Nth(2));
+        let fr = builder
+            .handle_response_template(&template, RESPONSE0)
+            .unwrap();
+        assert_eq!(values(&fr), [b"SOL"]);
+
+        let template = prefix_template(b"\"symbol\":\"", Some(3), None, MatchSelect::Max(2));
+        let fr = builder
+            .handle_response_template(&template, RESPONSE0)
+            .unwrap();
+        assert_eq!(values(&fr), [b"BTC", b"ETH"]);
+
+        let template = prefix_template(b"\"symbol\":\"", Some(3), None, MatchSelect::Nth(3));
+        let err = builder
+            .handle_response_template(&template, RESPONSE0)
+            .unwrap_err();
+        assert!(matches!(err, TemplateError::NoMatch(_)));
+
+        let template = prefix_template(b"\"volume\":", Some(3), None, MatchSelect::All);
+        let err = builder
+            .handle_response_template(&template, RESPONSE0)
+            .unwrap_err();
+        assert!(matches!(err, TemplateError::NoMatch(_)));
+    }
+
+    #[test]
+    fn test_handle_response_template_prefix_terminator() {
+        let mut builder = TLSInputBuilder::new().unwrap();
+
+        let template = prefix_template(b"\"price\":\"", None, Some(b"\""), MatchSelect::All);
+        let fr = builder
+            .handle_response_template(&template, RESPONSE0)
+            .unwrap();
+        let prices: [&[u8]; 3] = [b"67012.5", b"3120.75", b"142.1"];
+        assert_eq!(values(&fr), prices);
+
+        let mut template = prefix_template(b"\"price\":\"", Some(5), Some(b"\""), MatchSelect::All);
+        let err = builder
+            .handle_response_template(&template, RESPONSE0)
+            .unwrap_err();
+        assert!(matches!(err, TemplateError::Unterminated { .. }));
+
+        template.policy = TemplatePolicy::Skip;
+        let fr = builder
+            .handle_response_template(&template, RESPONSE0)
+            .unwrap();
+        assert_eq!(values(&fr), [b"142.1"]);
+
+        template.policy = TemplatePolicy::Truncate;
+        let fr = builder
+            .handle_response_template(&template, RESPONSE0)
+            .unwrap();
+        assert_eq!(values(&fr), [b"67012", b"3120.", b"142.1"]);
+
+        let template = prefix_template(b"\"price\":\"", None, None, MatchSelect::All);
+        let err = builder
+            .handle_response_template(&template, RESPONSE0)
+            .unwrap_err();
+        assert!(matches!(err, TemplateError::InvalidTemplate(_)));
+    }
+
+    #[test]
+    fn test_handle_response_template_json_path() {
+        let mut builder = TLSInputBuilder::new().unwrap();
+
+        let template = TemplateKind::JsonPath {
+            path: "$.data[1].price".into(),
+        }
+        .into();
+        let fr = builder
+            .handle_response_template(&template, RESPONSE0)
+            .unwrap();
+        assert_eq!(values(&fr), [b"3120.75"]);
+
+        let response: &[u8] = include_bytes!("../testdata/response1.http");
+        let template = TemplateKind::JsonPath {
+            path: "$.price".into(),
+        }
+        .into();
+        let fr = builder
+            .handle_response_template(&template, response)
+            .unwrap();
+        assert_eq!(fr[0].bytes, b"67012.5");
+        assert_eq!(
+            &response[fr[0].begin as usize..(fr[0].begin + fr[0].length) as usize],
+            b"67012.5"
+        );
+
+        let mut template = crate::Template::from(TemplateKind::JsonPath {
+            path: "$.data[3].price".into(),
+        });
+        let err = builder
+            .handle_response_template(&template, RESPONSE0)
+            .unwrap_err();
+        assert!(matches!(err, TemplateError::NoMatch(_)));
+
+        template.policy = TemplatePolicy::Skip;
+        let fr = builder
+            .handle_response_template(&template, RESPONSE0)
+            .unwrap();
+        assert!(fr.is_empty());
+    }
+
+    #[test]
+    fn test_handle_response_template_target() {
+        let response: &[u8] = include_bytes!("../testdata/response1.http");
+
+        let mut builder = TLSInputBuilder::new().unwrap();
+
+        let mut template = prefix_template(b"\"price\":\"", None, Some(b"\""), MatchSelect::All);
+        template.target = ResponseTarget::Body;
+        let fr = builder
+            .handle_response_template(&template, response)
+            .unwrap();
+        assert_eq!(fr[0].bytes, b"67012.5");
+        assert_eq!(
+            &response[fr[0].begin as usize..(fr[0].begin + fr[0].length) as usize],
+            b"67012.5"
+        );
+
+        template.kind = TemplateKind::Offset {
+            begin: 11,
+            length: 3,
+        };
+        let err = builder
+            .handle_response_template(&template, response)
+            .unwrap_err();
+        assert!(matches!(err, TemplateError::SpansChunks { .. }));
+
+        template.target = ResponseTarget::Status;
+        template.kind = TemplateKind::Offset {
+            begin: 0,
+            length: 3,
+        };
+        let fr = builder
+            .handle_response_template(&template, response)
+            .unwrap();
+        assert_eq!(fr[0].begin, 9);
+        assert_eq!(fr[0].bytes, b"200");
+
+        template.target = ResponseTarget::Header("content-type".into());
+        template.kind = TemplateKind::Offset {
+            begin: 12,
+            length: 4,
+        };
+        let fr = builder
+            .handle_response_template(&template, response)
+            .unwrap();
+        assert_eq!(fr[0].bytes, b"json");
+
+        template.target = ResponseTarget::Header("x-missing".into());
+        let err = builder
+            .handle_response_template(&template, response)
+            .unwrap_err();
+        assert!(matches!(err, TemplateError::MissingHeader(_)));
+    }
+
+    fn fixture_request(server: &FixtureServer) -> Request {
         let bytes = include_str!("../testdata/req0.json");
 
-        let mut req: Request = serde_json::from_str(bytes).unwrap();
+        let req: Request = serde_json::from_str(bytes).unwrap();
 
         let request_body =
-            "GET /get HTTP/1.1\r\nHost: httpbin.org\r\nAccept: */*\r\nConnection: Close\r\n\r\n";
+            "GET /prices HTTP/1.1\r\nHost: localhost\r\nAccept: */*\r\nConnection: Close\r\n\r\n";
 
-        req.request_info.request = request_body.as_bytes().to_vec().into();
+        server.request(req, request_body.as_bytes())
+    }
 
-        let mut builder = TLSInputBuilder::new().unwrap();
+    fn fixture_builder(server: &FixtureServer, config: TLSInputBuilderConfig) -> TLSInputBuilder {
+        TLSInputBuilder::with_config(config.add_root(server.ca_certificate())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_handle_response1() {
+        let server = FixtureServer::start([RESPONSE0.to_vec()]).unwrap();
+
+        let req = TemplatedRequest {
+            request: fixture_request(&server),
+            templates: vec![
+                TemplateKind::Offset {
+                    begin: 9,
+                    length: 3,
+                }
+                .into(),
+                prefix_template(b"\"price\":\"", None, Some(b"\""), MatchSelect::First),
+            ],
+        };
+
+        let mut builder = fixture_builder(&server, TLSInputBuilderConfig::default());
 
         let input = builder.handle_request_tls_call(req).await.unwrap();
 
-        println!(
-            "response: {}",
-            String::from_utf8(input.response.response.clone()).unwrap()
+        let ca =
+            build_root_store(&[RootSource::Certificate(server.ca_certificate())], &[]).unwrap();
+        assert_eq!(builder.trusted_root(), Some(root_fingerprint(&ca.roots[0])));
+
+        assert_eq!(input.response.response, RESPONSE0);
+        assert_eq!(input.response.filtered_responses_begin, [9, 125]);
+        assert_eq!(input.response.filtered_responses_length, [3, 7]);
+        assert_eq!(input.response.filtered_responses[1].as_ref(), b"67012.5");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with(b"GET /prices HTTP/1.1\r\n"));
+    }
+
+    #[tokio::test]
+    async fn test_tls_call_limits() {
+        let server = FixtureServer::start([RESPONSE0.to_vec()]).unwrap();
+
+        let limits = RequestLimits {
+            max_response_size: RESPONSE0.len() - 1,
+            ..Default::default()
+        };
+        let mut builder = fixture_builder(&server, TLSInputBuilderConfig::default().limits(limits));
+
+        let err = builder
+            .handle_request_tls_call(fixture_request(&server).into())
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<TlsCallError>(),
+            Some(TlsCallError::ResponseTooLarge(_))
+        ));
+
+        // Accepts connections but never answers the handshake.
+        let silent = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut req = fixture_request(&server);
+        req.request_info.remote_addr = silent.local_addr().unwrap().to_string();
+
+        let limits = RequestLimits {
+            handshake_timeout: Duration::from_millis(300),
+            ..Default::default()
+        };
+        let mut builder = fixture_builder(&server, TLSInputBuilderConfig::default().limits(limits));
+
+        let err = builder
+            .handle_request_tls_call(req.into())
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<TlsCallError>(),
+            Some(TlsCallError::HandshakeTimeout(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_replay_transcript() {
+        let server = FixtureServer::start([RESPONSE0.to_vec()]).unwrap();
+
+        let transcript_path = env::temp_dir().join(format!(
+            "zktls-transcript-{}-{}.json",
+            std::process::id(),
+            server.addr().port()
+        ));
+
+        let req = TemplatedRequest {
+            request: fixture_request(&server),
+            templates: vec![prefix_template(
+                b"\"symbol\":\"",
+                Some(3),
+                None,
+                MatchSelect::All,
+            )],
+        };
+
+        let mut builder = fixture_builder(
+            &server,
+            TLSInputBuilderConfig::default().record_transcript(&transcript_path),
         );
+        let input = builder.handle_request_tls_call(req.clone()).await.unwrap();
 
-        let guest_input_bytes = serde_json::to_string_pretty(&input).unwrap();
+        let transcript = Transcript::load(&transcript_path).unwrap();
+        fs::remove_file(&transcript_path).unwrap();
+        assert_eq!(transcript.root, builder.trusted_root());
 
-        fs::write("../../target/guest_input0.json", guest_input_bytes).unwrap();
+        let mut builder = TLSInputBuilder::new().unwrap();
+        let replayed = builder.build_replayed_input(req, transcript).unwrap();
+        assert!(builder.trusted_root().is_some());
+
+        assert_eq!(
+            serde_json::to_string(&input).unwrap(),
+            serde_json::to_string(&replayed).unwrap()
+        );
+        assert_eq!(server.requests().len(), 1);
     }
 }
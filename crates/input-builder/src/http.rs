@@ -0,0 +1,237 @@
+use std::{borrow::Cow, ops::Range};
+
+/// Error while parsing a HTTP/1.1 response.
+#[derive(Debug, thiserror::Error)]
+pub enum HttpError {
+    #[error("incomplete response head")]
+    Incomplete,
+    #[error("invalid response head: {0}")]
+    Invalid(#[from] httparse::Error),
+    #[error("invalid chunk at {0}")]
+    InvalidChunk(usize),
+}
+
+/// Spans of a HTTP/1.1 response on the raw response bytes.
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    /// Status code, like `200`.
+    pub status: Range<usize>,
+    /// Name and value of every header.
+    pub headers: Vec<(Range<usize>, Range<usize>)>,
+    /// Pieces of the body. There is one piece per chunk for a chunked body.
+    pub body: Vec<Range<usize>>,
+}
+
+const MAX_HEADERS: usize = 64;
+
+impl HttpResponse {
+    pub fn parse(raw: &[u8]) -> Result<Self, HttpError> {
+        let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+        let mut response = httparse::Response::new(&mut headers);
+
+        let head_length = match response.parse(raw)? {
+            httparse::Status::Complete(length) => length,
+            httparse::Status::Partial => return Err(HttpError::Incomplete),
+        };
+
+        // The status line is `HTTP/1.1 200 OK`, httparse already checked it.
+        let status_begin = raw
+            .iter()
+            .position(|b| *b == b' ')
+            .ok_or(HttpError::Incomplete)?
+            + 1;
+        let status = status_begin..status_begin + 3;
+
+        let headers: Vec<_> = response
+            .headers
+            .iter()
+            .map(|h| (span_of(raw, h.name.as_bytes()), span_of(raw, h.value)))
+            .collect();
+
+        let mut res = Self {
+            status,
+            headers,
+            body: Vec::new(),
+        };
+
+        let chunked = res
+            .header(raw, "transfer-encoding")
+            .is_some_and(|v| raw[v].trim_ascii().eq_ignore_ascii_case(b"chunked"));
+
+        res.body = if chunked {
+            parse_chunks(raw, head_length)?
+        } else {
+            let end = res
+                .header(raw, "content-length")
+                .and_then(|v| {
+                    std::str::from_utf8(raw[v].trim_ascii())
+                        .ok()?
+                        .parse::<usize>()
+                        .ok()
+                })
+                .map_or(raw.len(), |length| (head_length + length).min(raw.len()));
+
+            let body = head_length..end;
+            vec![body]
+        };
+
+        Ok(res)
+    }
+
+    /// Value of the first header named `name`, case-insensitive.
+    pub fn header(&self, raw: &[u8], name: &str) -> Option<Range<usize>> {
+        self.headers
+            .iter()
+            .find(|(n, _)| raw[n.clone()].eq_ignore_ascii_case(name.as_bytes()))
+            .map(|(_, v)| v.clone())
+    }
+
+    /// Decoded body.
+    pub fn body<'a>(&self, raw: &'a [u8]) -> Cow<'a, [u8]> {
+        match self.body.as_slice() {
+            [piece] => Cow::Borrowed(&raw[piece.clone()]),
+            pieces => Cow::Owned(
+                pieces
+                    .iter()
+                    .flat_map(|p| &raw[p.clone()])
+                    .copied()
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Map a range of the decoded body to the raw response.
+    ///
+    /// Returns `None` if the range crosses a chunk boundary, because it isn't
+    /// contiguous in the raw response.
+    pub fn body_range_to_raw(&self, range: Range<usize>) -> Option<Range<usize>> {
+        map_range(&self.body, range)
+    }
+}
+
+/// Map `range` of the concatenation of `pieces` to the raw bytes.
+pub(crate) fn map_range(pieces: &[Range<usize>], range: Range<usize>) -> Option<Range<usize>> {
+    let mut offset = 0;
+
+    for piece in pieces {
+        let piece_end = offset + piece.len();
+
+        if range.start < piece_end || (range.is_empty() && range.start == piece_end) {
+            if range.end > piece_end {
+                return None;
+            }
+
+            let begin = piece.start + range.start - offset;
+            return Some(begin..begin + range.len());
+        }
+
+        offset = piece_end;
+    }
+
+    (range.is_empty() && range.start == offset).then(|| {
+        let end = pieces.last().map_or(0, |p| p.end);
+        end..end
+    })
+}
+
+fn parse_chunks(raw: &[u8], mut pos: usize) -> Result<Vec<Range<usize>>, HttpError> {
+    let mut pieces = Vec::new();
+
+    loop {
+        let line_end = find_crlf(raw, pos).ok_or(HttpError::InvalidChunk(pos))?;
+
+        // Chunk extensions follow the size after a `;`.
+        let size = raw[pos..line_end]
+            .split(|b| *b == b';')
+            .next()
+            .and_then(|s| std::str::from_utf8(s.trim_ascii()).ok())
+            .and_then(|s| usize::from_str_radix(s, 16).ok())
+            .ok_or(HttpError::InvalidChunk(pos))?;
+
+        let begin = line_end + 2;
+        if size == 0 {
+            return Ok(pieces);
+        }
+
+        // The size comes from the server, a huge one must not overflow.
+        let end = begin
+            .checked_add(size)
+            .filter(|end| {
+                end.checked_add(2)
+                    .and_then(|crlf_end| raw.get(*end..crlf_end))
+                    == Some(b"\r\n")
+            })
+            .ok_or(HttpError::InvalidChunk(pos))?;
+
+        pieces.push(begin..end);
+        pos = end + 2;
+    }
+}
+
+fn find_crlf(raw: &[u8], from: usize) -> Option<usize> {
+    raw.get(from..)?
+        .windows(2)
+        .position(|w| w == b"\r\n")
+        .map(|p| p + from)
+}
+
+fn span_of(raw: &[u8], part: &[u8]) -> Range<usize> {
+    let begin = part.as_ptr() as usize - raw.as_ptr() as usize;
+    begin..begin + part.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESPONSE0: &[u8] = include_bytes!("../testdata/response0.http");
+    const RESPONSE1: &[u8] = include_bytes!("../testdata/response1.http");
+
+    #[test]
+    fn test_parse_response() {
+        let http = HttpResponse::parse(RESPONSE0).unwrap();
+
+        assert_eq!(&RESPONSE0[http.status.clone()], b"200");
+        let content_type = http.header(RESPONSE0, "Content-Type").unwrap();
+        assert_eq!(&RESPONSE0[content_type], b"application/json");
+        assert!(http.body(RESPONSE0).starts_with(b"{\"data\""));
+        assert_eq!(http.body.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_chunked_response() {
+        let http = HttpResponse::parse(RESPONSE1).unwrap();
+
+        assert_eq!(&RESPONSE1[http.status.clone()], b"200");
+        assert_eq!(http.body.len(), 3);
+
+        let body = http.body(RESPONSE1);
+        assert_eq!(body.as_ref(), br#"{"symbol":"BTC","price":"67012.5"}"#);
+
+        let begin = body.windows(7).position(|w| w == b"67012.5").unwrap();
+        let raw = http.body_range_to_raw(begin..begin + 7).unwrap();
+        assert_eq!(&RESPONSE1[raw], b"67012.5");
+
+        // `BTC` is split between the first two chunks.
+        let begin = body.windows(3).position(|w| w == b"BTC").unwrap();
+        assert!(http.body_range_to_raw(begin..begin + 3).is_none());
+    }
+
+    #[test]
+    fn test_parse_huge_chunk_size() {
+        let head = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n";
+        let size_length = format!("{:x}", usize::MAX).len();
+        let begin = head.len() + size_length + 2;
+
+        // The chunk would end one byte before `usize::MAX`, its CRLF after it.
+        for size in [usize::MAX - begin - 1, usize::MAX - begin, usize::MAX] {
+            let mut raw = head.to_vec();
+            raw.extend_from_slice(format!("{size:0size_length$x}\r\ndata\r\n0\r\n\r\n").as_bytes());
+
+            assert!(matches!(
+                HttpResponse::parse(&raw),
+                Err(HttpError::InvalidChunk(pos)) if pos == head.len()
+            ));
+        }
+    }
+}
@@ -0,0 +1,324 @@
+use std::ops::Range;
+
+/// Maximum nesting of arrays and objects, deeper documents are rejected instead
+/// of exhausting the stack.
+const MAX_DEPTH: usize = 128;
+
+/// Error of a JSON path template.
+#[derive(Debug, thiserror::Error)]
+pub enum JsonPathError {
+    #[error("invalid JSON path {0}")]
+    InvalidPath(String),
+    #[error("invalid JSON at {0}")]
+    InvalidJson(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parse a JSON path like `$.data.prices[0]["usd"]`.
+fn parse_path(path: &str) -> Result<Vec<Segment>, JsonPathError> {
+    let invalid = || JsonPathError::InvalidPath(path.to_string());
+
+    let mut rest = path.strip_prefix('$').ok_or_else(invalid)?;
+    let mut segments = Vec::new();
+
+    while !rest.is_empty() {
+        if let Some(r) = rest.strip_prefix('.') {
+            let end = r.find(['.', '[']).unwrap_or(r.len());
+            if end == 0 {
+                return Err(invalid());
+            }
+
+            segments.push(Segment::Key(r[..end].to_string()));
+            rest = &r[end..];
+        } else if let Some(r) = rest.strip_prefix('[') {
+            let end = r.find(']').ok_or_else(invalid)?;
+            let inner = &r[..end];
+
+            let quoted = inner
+                .strip_prefix('\'')
+                .and_then(|s| s.strip_suffix('\''))
+                .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+
+            let segment = match quoted {
+                Some(key) => Segment::Key(key.to_string()),
+                None => Segment::Index(inner.parse().map_err(|_| invalid())?),
+            };
+
+            segments.push(segment);
+            rest = &r[end + 1..];
+        } else {
+            return Err(invalid());
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Find the span of the value at `path` in the JSON document `json`.
+///
+/// The span of a string is its content without the quotes, with escapes left
+/// as they are. Other values span their whole token. Returns `None` if the path
+/// doesn't exist in the document.
+pub fn find_json_path(json: &[u8], path: &str) -> Result<Option<Range<usize>>, JsonPathError> {
+    let segments = parse_path(path)?;
+    let mut scanner = Scanner { json, pos: 0 };
+
+    for segment in &segments {
+        scanner.skip_whitespace();
+
+        let found = match (segment, scanner.peek()) {
+            (Segment::Key(key), Some(b'{')) => scanner.enter_object(key)?,
+            (Segment::Index(index), Some(b'[')) => scanner.enter_array(*index)?,
+            (_, Some(_)) => false,
+            (_, None) => return Err(JsonPathError::InvalidJson(scanner.pos)),
+        };
+
+        if !found {
+            return Ok(None);
+        }
+    }
+
+    scanner.skip_whitespace();
+    let begin = scanner.pos;
+    scanner.skip_value()?;
+
+    if json[begin] == b'"' {
+        return Ok(Some(begin + 1..scanner.pos - 1));
+    }
+
+    Ok(Some(begin..scanner.pos))
+}
+
+struct Scanner<'a> {
+    json: &'a [u8],
+    pos: usize,
+}
+
+impl Scanner<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.json.get(self.pos).copied()
+    }
+
+    fn invalid(&self) -> JsonPathError {
+        JsonPathError::InvalidJson(self.pos)
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), JsonPathError> {
+        self.skip_whitespace();
+        if self.peek() != Some(byte) {
+            return Err(self.invalid());
+        }
+
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\r' | b'\n')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Move to the value of `key`, returning false if the object has no such key.
+    fn enter_object(&mut self, key: &str) -> Result<bool, JsonPathError> {
+        self.expect(b'{')?;
+        self.skip_whitespace();
+
+        if self.peek() == Some(b'}') {
+            return Ok(false);
+        }
+
+        loop {
+            self.skip_whitespace();
+            let name = self.read_string()?;
+            self.expect(b':')?;
+
+            if name == key {
+                return Ok(true);
+            }
+
+            self.skip_value()?;
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => return Ok(false),
+                _ => return Err(self.invalid()),
+            }
+        }
+    }
+
+    /// Move to the element at `index`, returning false if the array is shorter.
+    fn enter_array(&mut self, index: usize) -> Result<bool, JsonPathError> {
+        self.expect(b'[')?;
+        self.skip_whitespace();
+
+        if self.peek() == Some(b']') {
+            return Ok(false);
+        }
+
+        for _ in 0..index {
+            self.skip_value()?;
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => return Ok(false),
+                _ => return Err(self.invalid()),
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Read a string and decode its escapes.
+    fn read_string(&mut self) -> Result<String, JsonPathError> {
+        let begin = self.pos;
+        self.skip_string()?;
+
+        serde_json::from_slice(&self.json[begin..self.pos])
+            .map_err(|_| JsonPathError::InvalidJson(begin))
+    }
+
+    fn skip_string(&mut self) -> Result<(), JsonPathError> {
+        self.expect(b'"')?;
+
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(b'\\') => self.pos += 2,
+                Some(_) => self.pos += 1,
+                None => return Err(self.invalid()),
+            }
+        }
+    }
+
+    fn skip_value(&mut self) -> Result<(), JsonPathError> {
+        self.skip_nested(0)
+    }
+
+    fn skip_nested(&mut self, depth: usize) -> Result<(), JsonPathError> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(b'"') => self.skip_string(),
+            Some(b'{' | b'[') if depth == MAX_DEPTH => Err(self.invalid()),
+            Some(open @ (b'{' | b'[')) => {
+                let close = if open == b'{' { b'}' } else { b']' };
+                self.pos += 1;
+                self.skip_whitespace();
+
+                if self.peek() == Some(close) {
+                    self.pos += 1;
+                    return Ok(());
+                }
+
+                loop {
+                    if open == b'{' {
+                        self.skip_whitespace();
+                        self.skip_string()?;
+                        self.expect(b':')?;
+                    }
+
+                    self.skip_nested(depth + 1)?;
+                    self.skip_whitespace();
+
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(c) if c == close => {
+                            self.pos += 1;
+                            return Ok(());
+                        }
+                        _ => return Err(self.invalid()),
+                    }
+                }
+            }
+            Some(b'-' | b'0'..=b'9' | b't' | b'f' | b'n') => {
+                let begin = self.pos;
+                while matches!(
+                    self.peek(),
+                    Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9' | b'a'..=b'z')
+                ) {
+                    self.pos += 1;
+                }
+
+                let token = &self.json[begin..self.pos];
+                if token.starts_with(b"t") && token != b"true"
+                    || token.starts_with(b"f") && token != b"false"
+                    || token.starts_with(b"n") && token != b"null"
+                {
+                    return Err(JsonPathError::InvalidJson(begin));
+                }
+
+                Ok(())
+            }
+            _ => Err(self.invalid()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSON: &[u8] =
+        br#"{"data": {"price": "67012.5", "volume": 12.5e3, "tags": ["a", {"key": null}], "ok": true}}"#;
+
+    fn value(path: &str) -> Option<&'static [u8]> {
+        find_json_path(JSON, path).unwrap().map(|r| &JSON[r])
+    }
+
+    #[test]
+    fn test_find_json_path() {
+        assert_eq!(value("$.data.price"), Some(&b"67012.5"[..]));
+        assert_eq!(value("$.data.volume"), Some(&b"12.5e3"[..]));
+        assert_eq!(value("$['data'].tags[0]"), Some(&b"a"[..]));
+        assert_eq!(value("$.data.tags[1][\"key\"]"), Some(&b"null"[..]));
+        assert_eq!(value("$.data.tags[1]"), Some(&br#"{"key": null}"#[..]));
+        assert_eq!(value("$.data.ok"), Some(&b"true"[..]));
+        assert_eq!(value("$.data.missing"), None);
+        assert_eq!(value("$.data.tags[2]"), None);
+        assert_eq!(value("$.data.price.inner"), None);
+
+        assert!(find_json_path(JSON, "data.price").is_err());
+        assert!(find_json_path(b"{\"data\": [", "$.data[0]").is_err());
+    }
+
+    #[test]
+    fn test_nesting_limit() {
+        let nested = |depth: usize| {
+            let mut json = br#"{"data": "#.to_vec();
+            json.resize(json.len() + depth, b'[');
+            json.resize(json.len() + depth, b']');
+            json.push(b'}');
+            json
+        };
+
+        assert!(find_json_path(&nested(MAX_DEPTH), "$.data")
+            .unwrap()
+            .is_some());
+        assert!(matches!(
+            find_json_path(&nested(MAX_DEPTH + 1), "$.data"),
+            Err(JsonPathError::InvalidJson(_))
+        ));
+
+        // Used to overflow the stack.
+        let body = nested(1 << 20);
+        assert!(matches!(
+            find_json_path(&body, "$.data"),
+            Err(JsonPathError::InvalidJson(_))
+        ));
+        assert!(matches!(
+            find_json_path(&body, "$.other"),
+            Err(JsonPathError::InvalidJson(_))
+        ));
+    }
+}
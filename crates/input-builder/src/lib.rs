@@ -4,8 +4,46 @@ pub use request::*;
 mod handler;
 pub use handler::*;
 
+mod config;
+pub use config::*;
+
+mod regex_cache;
+pub use regex_cache::*;
+
+mod template;
+pub use template::*;
+
+mod error;
+pub use error::*;
+
+mod transcript;
+pub use transcript::*;
+
+mod roots;
+pub use roots::*;
+
+mod http;
+pub use http::*;
+
+mod json;
+pub use json::*;
+
+mod request_builder;
+pub use request_builder::*;
+
+#[derive(Debug, Clone)]
 pub struct FilteredResponse {
     pub begin: u64,
     pub length: u64,
     pub bytes: Vec<u8>,
 }
+
+impl FilteredResponse {
+    fn new(response: &[u8], range: std::ops::Range<usize>) -> Self {
+        Self {
+            begin: range.start as u64,
+            length: range.len() as u64,
+            bytes: response[range].to_vec(),
+        }
+    }
+}
@@ -1,8 +1,7 @@
 use std::num::NonZeroUsize;
 
-use anyhow::Result;
 use lru::LruCache;
-use regex::Regex;
+use regex::bytes::Regex;
 
 use crate::FilteredResponse;
 
@@ -17,7 +16,11 @@ impl RegexCache {
         }
     }
 
-    pub fn find(&mut self, pattern: &str, text: &str) -> Result<Vec<FilteredResponse>> {
+    pub fn find(
+        &mut self,
+        pattern: &str,
+        text: &[u8],
+    ) -> Result<Vec<FilteredResponse>, regex::Error> {
         let regex = self
             .cache
             .try_get_or_insert(pattern.to_string(), || Regex::new(pattern))?;
@@ -29,12 +32,10 @@ impl RegexCache {
             let begin = m.start();
             let length = m.len();
 
-            let s = m.as_str();
-
             let filtered_response = FilteredResponse {
                 begin: begin as u64,
                 length: length as u64,
-                bytes: s.as_bytes().to_vec(),
+                bytes: m.as_bytes().to_vec(),
             };
 
             filtered_responses.push(filtered_response);
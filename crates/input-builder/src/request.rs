@@ -1,31 +1,57 @@
 use std::{
-    io::{Read, Write},
-    net::TcpStream,
+    io::{self, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
     panic,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 
+use alloy_primitives::B256;
 use anyhow::Result;
 use rustls::{ClientConfig, ClientConnection, RootCertStore};
 use zktls_program_core::{GuestInputResponse, Request};
 use zktls_recordable_tls_provider::{crypto_provider, time_provider, RecordableStream};
 
-pub fn request_tls_call(request: &Request) -> Result<GuestInputResponse> {
-    let res = panic::catch_unwind(move || _request_tls_call(request))
+use crate::{chain_root, RequestLimits, TlsCallError};
+
+/// Interval of the socket timeouts, used to check deadlines and cancellation.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Response of a TLS call.
+pub struct TlsCallResponse {
+    pub response: GuestInputResponse,
+    /// Fingerprint of the trusted root which anchored the certificate chain of
+    /// the server, see [`chain_root`].
+    pub root: Option<B256>,
+}
+
+pub fn request_tls_call(
+    request: &Request,
+    root_store: Arc<RootCertStore>,
+    limits: &RequestLimits,
+    cancel: &AtomicBool,
+) -> Result<TlsCallResponse> {
+    let res = panic::catch_unwind(move || _request_tls_call(request, root_store, limits, cancel))
         .map_err(|e| anyhow::anyhow!("{:?}", e))??;
 
     Ok(res)
 }
 
-fn _request_tls_call(request: &Request) -> Result<GuestInputResponse> {
+fn _request_tls_call(
+    request: &Request,
+    root_store: Arc<RootCertStore>,
+    limits: &RequestLimits,
+    cancel: &AtomicBool,
+) -> Result<TlsCallResponse> {
     log::info!("Connecting to {}", request.request_info.remote_addr);
-    let stream = TcpStream::connect(&request.request_info.remote_addr)?;
+    let stream = connect(&request.request_info.remote_addr, limits.connect_timeout)?;
+    stream.set_read_timeout(Some(POLL_INTERVAL))?;
+    stream.set_write_timeout(Some(POLL_INTERVAL))?;
     let mut recordable_stream = RecordableStream::new(stream);
 
-    let root_store = RootCertStore {
-        roots: webpki_roots::TLS_SERVER_ROOTS.into(),
-    };
-
     let crypto_provider = crypto_provider();
     let time_provider = time_provider();
 
@@ -34,23 +60,49 @@ fn _request_tls_call(request: &Request) -> Result<GuestInputResponse> {
     let config =
         ClientConfig::builder_with_details(Arc::new(crypto_provider), time_provider.clone())
             .with_safe_default_protocol_versions()?
-            .with_root_certificates(root_store)
+            .with_root_certificates(root_store.clone())
             .with_no_client_auth();
 
     let server_name = String::from(&request.request_info.server_name).try_into()?;
 
     let mut tls_stream = ClientConnection::new(Arc::new(config), server_name)?;
 
-    let mut tls = rustls::Stream::new(&mut tls_stream, &mut recordable_stream);
+    let deadline = Instant::now() + limits.handshake_timeout;
+    drive_io(
+        &mut tls_stream,
+        &mut recordable_stream,
+        deadline,
+        cancel,
+        |conn| !conn.is_handshaking(),
+    )
+    .map_err(|e| timeout_as(e, TlsCallError::HandshakeTimeout(limits.handshake_timeout)))?;
+
+    let root = tls_stream
+        .peer_certificates()
+        .and_then(|chain| chain_root(&root_store, chain));
+    match root {
+        Some(root) => log::info!("server certificate chain anchored by root {}", root),
+        None => log::warn!("root of the server certificate chain not identified"),
+    }
 
     let request_data = request.request_info.request.as_ref();
 
-    tls.write_all(request_data)?;
+    tls_stream.writer().write_all(request_data)?;
+
+    let deadline = Instant::now() + limits.read_timeout;
+    drive_io(
+        &mut tls_stream,
+        &mut recordable_stream,
+        deadline,
+        cancel,
+        |conn| !conn.wants_write(),
+    )
+    .map_err(|e| timeout_as(e, TlsCallError::ReadTimeout(limits.read_timeout)))?;
 
     log::info!("Sending request complete");
 
-    let mut response = Vec::new();
-    tls.read_to_end(&mut response)?;
+    let mut tls = rustls::Stream::new(&mut tls_stream, &mut recordable_stream);
+    let response = read_response(&mut tls, limits, cancel)?;
     tls.flush()?;
 
     recordable_stream.flush()?;
@@ -69,7 +121,7 @@ fn _request_tls_call(request: &Request) -> Result<GuestInputResponse> {
         stream.extend(td.to_bytes());
     }
 
-    Ok(GuestInputResponse {
+    let response = GuestInputResponse {
         time,
         stream,
         random,
@@ -77,7 +129,121 @@ fn _request_tls_call(request: &Request) -> Result<GuestInputResponse> {
         filtered_responses_begin: vec![],
         filtered_responses_length: vec![],
         filtered_responses: vec![],
-    })
+    };
+
+    Ok(TlsCallResponse { response, root })
+}
+
+fn connect(remote_addr: &str, timeout: Duration) -> Result<TcpStream> {
+    let mut last_error = None;
+
+    for addr in remote_addr.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    match last_error {
+        Some(e) if is_timeout(&e) => Err(TlsCallError::ConnectTimeout(timeout).into()),
+        Some(e) => Err(e.into()),
+        None => Err(anyhow::anyhow!("{} resolves to no address", remote_addr)),
+    }
+}
+
+/// Drive the TLS connection until `done`, failing with a timeout after `deadline`.
+fn drive_io<S: Read + Write>(
+    conn: &mut ClientConnection,
+    stream: &mut S,
+    deadline: Instant,
+    cancel: &AtomicBool,
+    done: impl Fn(&ClientConnection) -> bool,
+) -> Result<(), DriveError> {
+    while !done(conn) {
+        check_cancelled(cancel)?;
+
+        match conn.complete_io(stream) {
+            Ok((0, 0)) => {
+                return Err(DriveError::Other(anyhow::anyhow!(
+                    "connection closed by the server"
+                )))
+            }
+            Ok(_) => {}
+            Err(e) if is_timeout(&e) && Instant::now() < deadline => {}
+            Err(e) if is_timeout(&e) => return Err(DriveError::Timeout),
+            Err(e) => return Err(DriveError::Other(e.into())),
+        }
+    }
+
+    Ok(())
+}
+
+/// Read until the server closes the connection.
+///
+/// The read timeout is reset every time data is received.
+fn read_response(
+    tls: &mut impl Read,
+    limits: &RequestLimits,
+    cancel: &AtomicBool,
+) -> Result<Vec<u8>> {
+    let mut response = Vec::new();
+    let mut buf = [0u8; 16 * 1024];
+    let mut deadline = Instant::now() + limits.read_timeout;
+
+    loop {
+        check_cancelled(cancel)?;
+
+        match tls.read(&mut buf) {
+            Ok(0) => return Ok(response),
+            Ok(n) => {
+                if response.len() + n > limits.max_response_size {
+                    return Err(TlsCallError::ResponseTooLarge(limits.max_response_size).into());
+                }
+
+                response.extend_from_slice(&buf[..n]);
+                deadline = Instant::now() + limits.read_timeout;
+            }
+            Err(e) if is_timeout(&e) && Instant::now() < deadline => {}
+            Err(e) if is_timeout(&e) => {
+                return Err(TlsCallError::ReadTimeout(limits.read_timeout).into())
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+enum DriveError {
+    Timeout,
+    Other(anyhow::Error),
+}
+
+impl From<TlsCallError> for DriveError {
+    fn from(e: TlsCallError) -> Self {
+        DriveError::Other(e.into())
+    }
+}
+
+fn timeout_as(e: DriveError, timeout: TlsCallError) -> anyhow::Error {
+    match e {
+        DriveError::Timeout => timeout.into(),
+        DriveError::Other(e) => e,
+    }
+}
+
+fn check_cancelled(cancel: &AtomicBool) -> Result<(), TlsCallError> {
+    if cancel.load(Ordering::Relaxed) {
+        return Err(TlsCallError::Cancelled);
+    }
+
+    Ok(())
+}
+
+fn is_timeout(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
 }
 
 // #[cfg(test)]
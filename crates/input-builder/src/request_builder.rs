@@ -0,0 +1,205 @@
+use anyhow::Result;
+use url::Url;
+use zktls_program_core::Request;
+
+/// Request with zeroed target and origin, used when no base request is given.
+const DEFAULT_REQUEST: &str = r#"{
+  "version": 1,
+  "request_info": {
+    "request": "0x",
+    "remote_addr": "",
+    "server_name": ""
+  },
+  "response_template": [],
+  "target": {
+    "client": "0x0000000000000000000000000000000000000000",
+    "prover_id": "0x0000000000000000000000000000000000000000000000000000000000000000",
+    "submit_network_id": 1
+  },
+  "origin": {
+    "type": "secp256k1",
+    "signature": "0x0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+    "nonce": 0
+  }
+}"#;
+
+/// Build a [`Request`] from an URL, method, headers and body.
+///
+/// The raw HTTP/1.1 request gets a `Host` header, a `Content-Length` header when
+/// there is a body and `Connection: close`, unless they are set explicitly.
+pub struct RequestBuilder {
+    url: Url,
+    method: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl RequestBuilder {
+    pub fn new(url: &str) -> Result<Self> {
+        let url = Url::parse(url)?;
+
+        if url.scheme() != "https" {
+            return Err(anyhow::anyhow!("only https URLs are supported: {}", url));
+        }
+
+        if url.host_str().is_none() {
+            return Err(anyhow::anyhow!("URL has no host: {}", url));
+        }
+
+        Ok(Self {
+            url,
+            method: "GET".into(),
+            headers: Vec::new(),
+            body: Vec::new(),
+        })
+    }
+
+    pub fn method(mut self, method: impl Into<String>) -> Self {
+        self.method = method.into().to_ascii_uppercase();
+        self
+    }
+
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// `host:port` to connect to.
+    pub fn remote_addr(&self) -> String {
+        let host = self.url.host_str().expect("checked in new");
+        let port = self.url.port_or_known_default().unwrap_or(443);
+
+        format!("{}:{}", host, port)
+    }
+
+    /// Server name used for TLS, the host of the URL.
+    pub fn server_name(&self) -> String {
+        self.url
+            .host_str()
+            .expect("checked in new")
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string()
+    }
+
+    /// Raw HTTP/1.1 request.
+    pub fn http_request(&self) -> Result<Vec<u8>> {
+        if self.method.is_empty() || !self.method.bytes().all(is_token) {
+            return Err(anyhow::anyhow!("invalid method {}", self.method));
+        }
+
+        for (name, value) in &self.headers {
+            if name.is_empty() || !name.bytes().all(is_token) {
+                return Err(anyhow::anyhow!("invalid header name {}", name));
+            }
+            if value.bytes().any(|b| b == b'\r' || b == b'\n') {
+                return Err(anyhow::anyhow!("invalid value of header {}", name));
+            }
+        }
+
+        let has_header = |name: &str| {
+            self.headers
+                .iter()
+                .any(|(n, _)| n.eq_ignore_ascii_case(name))
+        };
+
+        let mut target = self.url.path().to_string();
+        if let Some(query) = self.url.query() {
+            target.push('?');
+            target.push_str(query);
+        }
+
+        let mut request = format!("{} {} HTTP/1.1\r\n", self.method, target);
+
+        if !has_header("host") {
+            let host = self.url.host_str().expect("checked in new");
+            match self.url.port() {
+                Some(port) => request.push_str(&format!("Host: {}:{}\r\n", host, port)),
+                None => request.push_str(&format!("Host: {}\r\n", host)),
+            }
+        }
+
+        for (name, value) in &self.headers {
+            request.push_str(&format!("{}: {}\r\n", name, value));
+        }
+
+        if !self.body.is_empty() && !has_header("content-length") {
+            request.push_str(&format!("Content-Length: {}\r\n", self.body.len()));
+        }
+
+        if !has_header("connection") {
+            request.push_str("Connection: close\r\n");
+        }
+
+        request.push_str("\r\n");
+
+        let mut request = request.into_bytes();
+        request.extend_from_slice(&self.body);
+
+        Ok(request)
+    }
+
+    /// Build the request, with zeroed target and origin.
+    pub fn build(&self) -> Result<Request> {
+        self.build_with(serde_json::from_str(DEFAULT_REQUEST)?)
+    }
+
+    /// Build the request, keeping everything but the request info of `base`.
+    pub fn build_with(&self, mut base: Request) -> Result<Request> {
+        base.request_info.request = self.http_request()?.into();
+        base.request_info.remote_addr = self.remote_addr();
+        base.request_info.server_name = self.server_name();
+
+        Ok(base)
+    }
+}
+
+fn is_token(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_request() {
+        let builder = RequestBuilder::new("https://api.example.com/v1/price?symbol=BTC")
+            .unwrap()
+            .method("post")
+            .header("Content-Type", "application/json")
+            .body(r#"{"a":1}"#);
+
+        let request = builder.build().unwrap();
+
+        assert_eq!(request.request_info.remote_addr, "api.example.com:443");
+        assert_eq!(request.request_info.server_name, "api.example.com");
+        assert_eq!(
+            request.request_info.request.as_ref(),
+            b"POST /v1/price?symbol=BTC HTTP/1.1\r\nHost: api.example.com\r\nContent-Type: application/json\r\nContent-Length: 7\r\nConnection: close\r\n\r\n{\"a\":1}"
+        );
+
+        let builder = RequestBuilder::new("https://127.0.0.1:8443/").unwrap();
+        let base: Request = serde_json::from_str(include_str!("../testdata/req0.json")).unwrap();
+        let request = builder.build_with(base).unwrap();
+
+        assert_eq!(request.request_info.remote_addr, "127.0.0.1:8443");
+        assert_eq!(request.request_info.server_name, "127.0.0.1");
+        assert_eq!(
+            request.request_info.request.as_ref(),
+            b"GET / HTTP/1.1\r\nHost: 127.0.0.1:8443\r\nConnection: close\r\n\r\n"
+        );
+
+        assert!(RequestBuilder::new("http://example.com").is_err());
+        assert!(RequestBuilder::new("https://example.com")
+            .unwrap()
+            .header("X-Bad", "a\r\nb")
+            .http_request()
+            .is_err());
+    }
+}
@@ -0,0 +1,200 @@
+use std::{fs::File, io::BufReader, path::PathBuf};
+
+use alloy_primitives::B256;
+use anyhow::Result;
+use rustls::{
+    pki_types::{CertificateDer, TrustAnchor},
+    RootCertStore,
+};
+use sha2::{Digest, Sha256};
+
+/// Source of root certificates trusted by TLS calls.
+#[derive(Debug, Clone)]
+pub enum RootSource {
+    /// Mozilla roots bundled with `webpki-roots`.
+    WebPki,
+    /// Roots of the operating system certificate store.
+    Native,
+    /// PEM bundle of certificates.
+    PemFile(PathBuf),
+    /// Single DER encoded certificate.
+    Certificate(CertificateDer<'static>),
+}
+
+/// Fingerprint of a trust anchor, the SHA-256 of its `subjectPublicKeyInfo`.
+pub fn root_fingerprint(anchor: &TrustAnchor<'_>) -> B256 {
+    B256::from_slice(&Sha256::digest(anchor.subject_public_key_info.as_ref()))
+}
+
+/// Fingerprint of the root of `root_store` which anchors `chain`, the
+/// certificates sent by the server, end-entity first.
+///
+/// The root is the one whose subject is the issuer of the last certificate of
+/// the chain, or that certificate itself if the server sent its root. `None` if
+/// no root or several roots with different keys match.
+pub fn chain_root(root_store: &RootCertStore, chain: &[CertificateDer<'_>]) -> Option<B256> {
+    let last = chain.last()?;
+    let (subject_public_key_info, issuer) = certificate_names(last)?;
+
+    let mut matches = root_store.roots.iter().filter(|anchor| {
+        anchor.subject.as_ref() == issuer
+            || anchor.subject_public_key_info.as_ref() == subject_public_key_info
+    });
+
+    let root = root_fingerprint(matches.next()?);
+    matches
+        .all(|anchor| root_fingerprint(anchor) == root)
+        .then_some(root)
+}
+
+/// `subjectPublicKeyInfo` and issuer name of a certificate, both without their
+/// DER header as in a [`TrustAnchor`].
+fn certificate_names<'a>(certificate: &'a CertificateDer<'_>) -> Option<(&'a [u8], &'a [u8])> {
+    const SEQUENCE: u8 = 0x30;
+    const INTEGER: u8 = 0x02;
+    const VERSION: u8 = 0xa0;
+
+    let (certificate, _) = der_value(certificate.as_ref(), SEQUENCE)?;
+    let (tbs, _) = der_value(certificate, SEQUENCE)?;
+
+    let tbs = match der_value(tbs, VERSION) {
+        Some((_, rest)) => rest,
+        None => tbs,
+    };
+    let (_, rest) = der_value(tbs, INTEGER)?; // serialNumber
+    let (_, rest) = der_value(rest, SEQUENCE)?; // signature
+    let (issuer, rest) = der_value(rest, SEQUENCE)?;
+    let (_, rest) = der_value(rest, SEQUENCE)?; // validity
+    let (_, rest) = der_value(rest, SEQUENCE)?; // subject
+    let (subject_public_key_info, _) = der_value(rest, SEQUENCE)?;
+
+    Some((subject_public_key_info, issuer))
+}
+
+/// Value of the DER element at the start of `input` if it has `tag`, and the
+/// bytes after it.
+fn der_value(input: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+    let (&actual, input) = input.split_first()?;
+    if actual != tag {
+        return None;
+    }
+
+    let (&first, mut input) = input.split_first()?;
+    let length = match first {
+        0x00..=0x7f => first as usize,
+        0x81..=0x84 => {
+            let size = (first & 0x7f) as usize;
+            let (bytes, rest) = input.split_at_checked(size)?;
+            input = rest;
+            bytes
+                .iter()
+                .fold(0usize, |length, b| (length << 8) | *b as usize)
+        }
+        _ => return None,
+    };
+
+    let (value, rest) = input.split_at_checked(length)?;
+    Some((value, rest))
+}
+
+/// Build the root store from `sources`.
+///
+/// When `allowed` is not empty, only the roots with one of these fingerprints are
+/// trusted, and every allowed fingerprint must be found in the sources.
+pub fn build_root_store(sources: &[RootSource], allowed: &[B256]) -> Result<RootCertStore> {
+    let mut root_store = RootCertStore::empty();
+
+    for source in sources {
+        match source {
+            RootSource::WebPki => root_store
+                .roots
+                .extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned()),
+            RootSource::Native => {
+                let res = rustls_native_certs::load_native_certs();
+                for e in &res.errors {
+                    log::warn!("Failed to load native root: {}", e);
+                }
+
+                let (added, ignored) = root_store.add_parsable_certificates(res.certs);
+                log::info!("Loaded {} native roots, ignored {}", added, ignored);
+            }
+            RootSource::PemFile(path) => {
+                let mut reader = BufReader::new(File::open(path)?);
+                for certificate in rustls_pemfile::certs(&mut reader) {
+                    root_store.add(certificate?)?;
+                }
+            }
+            RootSource::Certificate(certificate) => root_store.add(certificate.clone())?,
+        }
+    }
+
+    if !allowed.is_empty() {
+        root_store
+            .roots
+            .retain(|anchor| allowed.contains(&root_fingerprint(anchor)));
+
+        for fingerprint in allowed {
+            if !root_store
+                .roots
+                .iter()
+                .any(|anchor| root_fingerprint(anchor) == *fingerprint)
+            {
+                return Err(anyhow::anyhow!("allowed root {} not found", fingerprint));
+            }
+        }
+    }
+
+    if root_store.is_empty() {
+        return Err(anyhow::anyhow!("no trusted root certificate"));
+    }
+
+    Ok(root_store)
+}
+
+#[cfg(test)]
+mod tests {
+    use zktls_tls_fixture::FixtureServer;
+
+    use super::*;
+
+    #[test]
+    fn test_build_root_store_allowlist() {
+        let server = FixtureServer::start([]).unwrap();
+
+        let sources = [
+            RootSource::WebPki,
+            RootSource::Certificate(server.ca_certificate()),
+        ];
+
+        let root_store = build_root_store(&sources, &[]).unwrap();
+        assert_eq!(root_store.len(), webpki_roots::TLS_SERVER_ROOTS.len() + 1);
+
+        let fingerprint = root_fingerprint(root_store.roots.last().unwrap());
+
+        let root_store = build_root_store(&sources, &[fingerprint]).unwrap();
+        assert_eq!(root_store.len(), 1);
+
+        assert!(build_root_store(&sources, &[B256::ZERO]).is_err());
+        assert!(build_root_store(&[], &[]).is_err());
+    }
+
+    #[test]
+    fn test_chain_root() {
+        let server = FixtureServer::start([]).unwrap();
+        let sources = [
+            RootSource::WebPki,
+            RootSource::Certificate(server.ca_certificate()),
+        ];
+        let root_store = build_root_store(&sources, &[]).unwrap();
+        let root = root_fingerprint(root_store.roots.last().unwrap());
+
+        // A chain ending with a certificate issued by the root, or with the root.
+        let chain = [server.certificate(), server.ca_certificate()];
+        assert_eq!(chain_root(&root_store, &chain[..1]), Some(root));
+        assert_eq!(chain_root(&root_store, &chain), Some(root));
+
+        let webpki_only = build_root_store(&[RootSource::WebPki], &[]).unwrap();
+        assert_eq!(chain_root(&webpki_only, &chain), None);
+        assert_eq!(chain_root(&root_store, &[]), None);
+    }
+}
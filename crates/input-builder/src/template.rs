@@ -0,0 +1,172 @@
+use alloy_primitives::Bytes;
+use serde::{Deserialize, Serialize};
+use zktls_program_core::{Request, ResponseTemplate};
+
+/// Template used by the input builder to extract values from the response.
+///
+/// It is a superset of [`ResponseTemplate`]. Every template resolves to a list
+/// of `(begin, length)` spans on the response, so the guest only has to check
+/// offsets no matter which template was used.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Template {
+    #[serde(flatten)]
+    pub kind: TemplateKind,
+
+    /// Part of the response the template applies to.
+    ///
+    /// Offsets of the template are relative to the target, the extracted values
+    /// are mapped back to offsets of the raw response.
+    #[serde(default)]
+    pub target: ResponseTarget,
+
+    /// What to do when the template can't be applied to the response.
+    #[serde(default)]
+    pub policy: TemplatePolicy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TemplateKind {
+    /// Fixed span of the response.
+    Offset { begin: u64, length: u64 },
+    /// Bytes following the selected occurrences of `prefix`.
+    ///
+    /// The value ends at the first `terminator` after the prefix, or after
+    /// `length` bytes if there is no terminator. When both are set, the
+    /// terminator has to be found within `length` bytes.
+    Prefix {
+        prefix: Bytes,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        length: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        terminator: Option<Bytes>,
+        #[serde(default)]
+        select: MatchSelect,
+    },
+    /// Selected matches of the regex `pattern`.
+    Regex {
+        pattern: String,
+        #[serde(default)]
+        select: MatchSelect,
+    },
+    /// Value at the JSON `path`, like `$.data.price`.
+    ///
+    /// Applies to the body when the target is [`ResponseTarget::Raw`]. The value
+    /// of a string is its content without the quotes.
+    JsonPath { path: String },
+}
+
+impl TemplateKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TemplateKind::Offset { .. } => "offset",
+            TemplateKind::Prefix { .. } => "prefix",
+            TemplateKind::Regex { .. } => "regex",
+            TemplateKind::JsonPath { .. } => "json_path",
+        }
+    }
+}
+
+/// Part of the HTTP response a template applies to.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseTarget {
+    /// Raw response, including the status line and headers.
+    #[default]
+    Raw,
+    /// Status code.
+    Status,
+    /// Value of the first header with this name.
+    Header(String),
+    /// Body, with the chunked transfer encoding decoded.
+    Body,
+}
+
+/// Which matches of a prefix or regex template are extracted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchSelect {
+    /// Every match.
+    #[default]
+    All,
+    /// Only the first match.
+    First,
+    /// Only the match at this zero-based index.
+    Nth(usize),
+    /// At most this number of matches, from the beginning of the response.
+    Max(usize),
+}
+
+impl MatchSelect {
+    pub fn apply<T>(self, mut iter: impl Iterator<Item = T>) -> Vec<T> {
+        match self {
+            MatchSelect::All => iter.collect(),
+            MatchSelect::First => iter.take(1).collect(),
+            MatchSelect::Nth(n) => iter.nth(n).into_iter().collect(),
+            MatchSelect::Max(n) => iter.take(n).collect(),
+        }
+    }
+}
+
+/// Policy applied when a template is out of bounds or doesn't match.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplatePolicy {
+    /// Fail the whole request.
+    #[default]
+    Fail,
+    /// Ignore the value.
+    Skip,
+    /// Keep the part of the value inside the response, ignore it if nothing is left.
+    Truncate,
+}
+
+impl From<TemplateKind> for Template {
+    fn from(kind: TemplateKind) -> Self {
+        Self {
+            kind,
+            target: ResponseTarget::default(),
+            policy: TemplatePolicy::default(),
+        }
+    }
+}
+
+impl From<&ResponseTemplate> for Template {
+    fn from(template: &ResponseTemplate) -> Self {
+        match template {
+            ResponseTemplate::Offset { begin, length } => TemplateKind::Offset {
+                begin: *begin,
+                length: *length,
+            },
+            ResponseTemplate::Prefix { prefix, length } => TemplateKind::Prefix {
+                prefix: prefix.to_vec().into(),
+                length: Some(*length),
+                terminator: None,
+                select: MatchSelect::All,
+            },
+        }
+        .into()
+    }
+}
+
+/// Request with extra templates which are only understood by the input builder.
+///
+/// The templates are serialized next to the request fields, so a plain
+/// [`Request`] file is also a valid `TemplatedRequest`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TemplatedRequest {
+    #[serde(flatten)]
+    pub request: Request,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub templates: Vec<Template>,
+}
+
+impl From<Request> for TemplatedRequest {
+    fn from(request: Request) -> Self {
+        Self {
+            request,
+            templates: Vec::new(),
+        }
+    }
+}
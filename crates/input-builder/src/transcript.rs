@@ -0,0 +1,77 @@
+use std::{fs, path::Path};
+
+use alloy_primitives::B256;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use zktls_program_core::{GuestInputResponse, Request};
+
+pub const TRANSCRIPT_VERSION: u8 = 1;
+
+/// Recorded TLS session of a request.
+///
+/// It keeps the recorded stream together with the time and random values used
+/// during the handshake, so the same [`GuestInput`](zktls_program_core::GuestInput)
+/// can be built again without network access.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Transcript {
+    pub version: u8,
+    pub request: Request,
+    /// Response of the TLS call, before any template is applied.
+    pub response: GuestInputResponse,
+    /// Fingerprint of the root which anchored the certificate chain of the
+    /// server, see [`chain_root`](crate::chain_root).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<B256>,
+}
+
+impl Transcript {
+    pub fn new(request: Request, response: GuestInputResponse, root: Option<B256>) -> Self {
+        Self {
+            version: TRANSCRIPT_VERSION,
+            request,
+            response,
+            root,
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)?;
+        let transcript: Self = serde_json::from_slice(&bytes)?;
+
+        if transcript.version != TRANSCRIPT_VERSION {
+            return Err(anyhow::anyhow!(
+                "unsupported transcript version {} in {}",
+                transcript.version,
+                path.display()
+            ));
+        }
+
+        Ok(transcript)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let bytes = serde_json::to_vec_pretty(self)?;
+        fs::write(path, bytes)?;
+
+        Ok(())
+    }
+
+    /// Check the transcript was recorded for the same call as `request`.
+    pub fn check_request(&self, request: &Request) -> Result<()> {
+        let recorded = &self.request.request_info;
+        let info = &request.request_info;
+
+        if recorded.request != info.request
+            || recorded.remote_addr != info.remote_addr
+            || recorded.server_name != info.server_name
+        {
+            return Err(anyhow::anyhow!(
+                "transcript was recorded for a different request to {}",
+                recorded.remote_addr
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use zktls_core::ProveError;
+use zktls_input_builder::{TemplateFailure, TlsCallError};
+
+/// Whether a failed job is worth another attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    /// Transient failure, such as a network error or a proof deadline.
+    Retryable,
+    /// Failure which happens again on every attempt, such as an invalid
+    /// request or a guest program rejecting the input.
+    Fatal,
+}
+
+impl FailureKind {
+    /// Classify an error by the first known error of its chain.
+    ///
+    /// Unknown errors are retryable, the attempts of the queue bound them.
+    pub fn classify(e: &anyhow::Error) -> Self {
+        for cause in e.chain() {
+            if cause.is::<TemplateFailure>()
+                || cause.is::<serde_json::Error>()
+                || cause.is::<rustls::Error>()
+            {
+                return FailureKind::Fatal;
+            }
+
+            if let Some(e) = cause.downcast_ref::<TlsCallError>() {
+                return match e {
+                    TlsCallError::ResponseTooLarge(_) => FailureKind::Fatal,
+                    _ => FailureKind::Retryable,
+                };
+            }
+
+            if let Some(e) = cause.downcast_ref::<ProveError>() {
+                return match e {
+                    // The guest rejects the same input on every attempt.
+                    ProveError::GuestRejected(_) => FailureKind::Fatal,
+                    // Backends also panic on transient failures, such as the
+                    // SP1 network prover losing its connection.
+                    ProveError::Panicked(_)
+                    | ProveError::DeadlineExceeded(_)
+                    | ProveError::Cancelled => FailureKind::Retryable,
+                };
+            }
+
+            if let Some(e) = cause.downcast_ref::<std::io::Error>() {
+                // rustls reports handshake failures, such as an untrusted
+                // certificate, as I/O errors.
+                if e.get_ref().is_some_and(|inner| inner.is::<rustls::Error>()) {
+                    return FailureKind::Fatal;
+                }
+                return FailureKind::Retryable;
+            }
+        }
+
+        FailureKind::Retryable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io, time::Duration};
+
+    use anyhow::Context;
+
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let e = anyhow::Error::from(io::Error::from(io::ErrorKind::ConnectionReset));
+        assert_eq!(FailureKind::classify(&e), FailureKind::Retryable);
+
+        let e = anyhow::Error::from(ProveError::GuestRejected("invalid input".into()))
+            .context("proving job 1");
+        assert_eq!(FailureKind::classify(&e), FailureKind::Fatal);
+
+        let e = anyhow::Error::from(ProveError::Panicked("connection reset".into()));
+        assert_eq!(FailureKind::classify(&e), FailureKind::Retryable);
+
+        let e = Err::<(), _>(TlsCallError::ConnectTimeout(Duration::from_secs(1)))
+            .context("building input")
+            .unwrap_err();
+        assert_eq!(FailureKind::classify(&e), FailureKind::Retryable);
+
+        let e = serde_json::from_str::<u64>("x").unwrap_err().into();
+        assert_eq!(FailureKind::classify(&e), FailureKind::Fatal);
+    }
+}
@@ -0,0 +1,58 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use alloy_primitives::B256;
+use serde::{Deserialize, Serialize};
+use zktls_core::{ProofArtifact, ProofMode};
+use zktls_input_builder::TemplatedRequest;
+
+pub type JobId = u64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    /// Waiting for a worker, possibly after a retryable failure.
+    Pending,
+    Running,
+    Succeeded,
+    /// Failed with a fatal error, or out of attempts.
+    Failed,
+}
+
+impl JobState {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, JobState::Succeeded | JobState::Failed)
+    }
+}
+
+/// Proof request of the queue.
+///
+/// The guest input built for the job is stored apart, see [`JobQueue::input`](crate::JobQueue::input).
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub id: JobId,
+    pub state: JobState,
+    pub request: TemplatedRequest,
+    pub proof_mode: ProofMode,
+    /// Number of times a worker claimed the job.
+    pub attempts: u32,
+    /// Unix time in seconds.
+    pub created_at: u64,
+    pub updated_at: u64,
+    /// The job is not claimed before this Unix time.
+    pub retry_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artifact: Option<ProofArtifact>,
+    /// Root which anchored the certificate chain of the server when the input
+    /// was built, recorded with the input.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trusted_root: Option<B256>,
+}
+
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
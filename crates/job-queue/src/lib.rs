@@ -0,0 +1,8 @@
+mod job;
+pub use job::*;
+
+mod failure;
+pub use failure::*;
+
+mod queue;
+pub use queue::*;
@@ -0,0 +1,376 @@
+use std::{path::Path, time::Duration};
+
+use alloy_primitives::B256;
+use anyhow::{anyhow, Result};
+use redb::{Database, ReadableTable, ReadableTableMetadata, TableDefinition};
+use zktls_core::{ProofArtifact, ProofMode};
+use zktls_input_builder::TemplatedRequest;
+use zktls_program_core::GuestInput;
+
+use crate::{now, FailureKind, Job, JobId, JobState};
+
+/// Jobs, as JSON.
+const JOBS: TableDefinition<JobId, &[u8]> = TableDefinition::new("jobs");
+/// Guest inputs built for the jobs, as CBOR.
+const INPUTS: TableDefinition<JobId, &[u8]> = TableDefinition::new("inputs");
+/// Pending jobs by `(retry_at, id)`, so a claim doesn't read the finished jobs.
+const PENDING: TableDefinition<(u64, JobId), ()> = TableDefinition::new("pending");
+
+/// Durable queue of proof jobs, stored in a redb database.
+///
+/// Workers [`claim`](Self::claim) pending jobs and report the outcome with
+/// [`succeed`](Self::succeed) or [`fail`](Self::fail). Jobs still running when
+/// the queue is opened were interrupted by a crash, and are pending again.
+/// Opening the queue also rebuilds the index of the pending jobs.
+pub struct JobQueue {
+    db: Database,
+    max_attempts: u32,
+    backoff: Duration,
+}
+
+impl JobQueue {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let db = Database::create(path)?;
+
+        let txn = db.begin_write()?;
+        txn.delete_table(PENDING)?;
+        {
+            let mut jobs = txn.open_table(JOBS)?;
+            let mut pending = txn.open_table(PENDING)?;
+            txn.open_table(INPUTS)?;
+
+            let mut interrupted = Vec::new();
+            for entry in jobs.iter()? {
+                let (_, value) = entry?;
+                let job: Job = serde_json::from_slice(value.value())?;
+                match job.state {
+                    JobState::Pending => {
+                        pending.insert((job.retry_at, job.id), ())?;
+                    }
+                    JobState::Running => interrupted.push(job),
+                    _ => {}
+                }
+            }
+
+            for mut job in interrupted {
+                log::info!("resuming interrupted job {}", job.id);
+                job.state = JobState::Pending;
+                job.updated_at = now();
+                jobs.insert(job.id, serde_json::to_vec(&job)?.as_slice())?;
+                pending.insert((job.retry_at, job.id), ())?;
+            }
+        }
+        txn.commit()?;
+
+        Ok(Self {
+            db,
+            max_attempts: 3,
+            backoff: Duration::from_secs(30),
+        })
+    }
+
+    /// Attempts of a job before a retryable failure becomes fatal, 3 by default.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Delay before the retry of a job, multiplied by its attempts, 30 seconds by default.
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn push(&self, request: TemplatedRequest, proof_mode: ProofMode) -> Result<Job> {
+        let txn = self.db.begin_write()?;
+        let job = {
+            let mut jobs = txn.open_table(JOBS)?;
+            let id = match jobs.last()? {
+                Some((id, _)) => id.value() + 1,
+                None => 1,
+            };
+
+            let now = now();
+            let job = Job {
+                id,
+                state: JobState::Pending,
+                request,
+                proof_mode,
+                attempts: 0,
+                created_at: now,
+                updated_at: now,
+                retry_at: now,
+                error: None,
+                artifact: None,
+                trusted_root: None,
+            };
+            jobs.insert(id, serde_json::to_vec(&job)?.as_slice())?;
+            txn.open_table(PENDING)?.insert((now, id), ())?;
+
+            job
+        };
+        txn.commit()?;
+
+        Ok(job)
+    }
+
+    pub fn get(&self, id: JobId) -> Result<Option<Job>> {
+        let jobs = self.db.begin_read()?.open_table(JOBS)?;
+
+        match jobs.get(id)? {
+            Some(value) => Ok(Some(serde_json::from_slice(value.value())?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn list(&self) -> Result<Vec<Job>> {
+        let jobs = self.db.begin_read()?.open_table(JOBS)?;
+
+        let mut list = Vec::with_capacity(jobs.len()? as usize);
+        for entry in jobs.iter()? {
+            let (_, value) = entry?;
+            list.push(serde_json::from_slice(value.value())?);
+        }
+
+        Ok(list)
+    }
+
+    /// Take the oldest pending job which is due, and mark it running.
+    pub fn claim(&self) -> Result<Option<Job>> {
+        let now = now();
+
+        let txn = self.db.begin_write()?;
+        let claimed = {
+            let mut jobs = txn.open_table(JOBS)?;
+            let mut pending = txn.open_table(PENDING)?;
+
+            let mut claimed = None;
+            loop {
+                let first = pending.first()?.map(|(key, _)| key.value());
+                let Some((retry_at, id)) = first.filter(|(retry_at, _)| *retry_at <= now) else {
+                    break;
+                };
+                pending.remove((retry_at, id))?;
+
+                let job: Option<Job> = match jobs.get(id)? {
+                    Some(value) => Some(serde_json::from_slice(value.value())?),
+                    None => None,
+                };
+                // Skip the entries left behind by a job which changed since.
+                if let Some(job) =
+                    job.filter(|job| job.state == JobState::Pending && job.retry_at == retry_at)
+                {
+                    claimed = Some(job);
+                    break;
+                }
+            }
+
+            if let Some(job) = &mut claimed {
+                job.state = JobState::Running;
+                job.attempts += 1;
+                job.updated_at = now;
+                jobs.insert(job.id, serde_json::to_vec(&job)?.as_slice())?;
+            }
+
+            claimed
+        };
+        txn.commit()?;
+
+        Ok(claimed)
+    }
+
+    /// Guest input built by a previous attempt of the job.
+    pub fn input(&self, id: JobId) -> Result<Option<GuestInput>> {
+        let inputs = self.db.begin_read()?.open_table(INPUTS)?;
+
+        match inputs.get(id)? {
+            Some(value) => Ok(Some(ciborium::from_reader(value.value())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Keep the guest input of the job, and the root its server was trusted
+    /// by, so retries don't call the server again.
+    pub fn set_input(
+        &self,
+        id: JobId,
+        input: &GuestInput,
+        trusted_root: Option<B256>,
+    ) -> Result<Job> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(input, &mut bytes)?;
+
+        let txn = self.db.begin_write()?;
+        let job = {
+            let mut jobs = txn.open_table(JOBS)?;
+
+            let mut job: Job = match jobs.get(id)? {
+                Some(value) => serde_json::from_slice(value.value())?,
+                None => return Err(anyhow!("no job {}", id)),
+            };
+            job.trusted_root = trusted_root;
+            jobs.insert(id, serde_json::to_vec(&job)?.as_slice())?;
+
+            txn.open_table(INPUTS)?.insert(id, bytes.as_slice())?;
+
+            job
+        };
+        txn.commit()?;
+
+        Ok(job)
+    }
+
+    pub fn succeed(&self, id: JobId, artifact: ProofArtifact) -> Result<Job> {
+        self.update(id, |job| {
+            job.state = JobState::Succeeded;
+            job.error = None;
+            job.artifact = Some(artifact);
+        })
+    }
+
+    /// Record a failure of the job, and queue it again if it is retryable and
+    /// has attempts left.
+    pub fn fail(&self, id: JobId, e: &anyhow::Error) -> Result<Job> {
+        let kind = FailureKind::classify(e);
+        let max_attempts = self.max_attempts;
+        let backoff = self.backoff;
+
+        self.update(id, |job| {
+            job.error = Some(format!("{:#}", e));
+
+            if kind == FailureKind::Retryable && job.attempts < max_attempts {
+                job.state = JobState::Pending;
+                job.retry_at = now() + backoff.as_secs() * job.attempts as u64;
+            } else {
+                job.state = JobState::Failed;
+            }
+        })
+    }
+
+    fn update(&self, id: JobId, f: impl FnOnce(&mut Job)) -> Result<Job> {
+        let txn = self.db.begin_write()?;
+        let job = {
+            let mut jobs = txn.open_table(JOBS)?;
+
+            let mut job: Job = match jobs.get(id)? {
+                Some(value) => serde_json::from_slice(value.value())?,
+                None => return Err(anyhow!("no job {}", id)),
+            };
+            f(&mut job);
+            job.updated_at = now();
+            jobs.insert(id, serde_json::to_vec(&job)?.as_slice())?;
+
+            if job.state == JobState::Pending {
+                txn.open_table(PENDING)?.insert((job.retry_at, id), ())?;
+            }
+
+            // The input is only needed while the job can still be attempted,
+            // it goes with the state change so a crash can't leave it behind.
+            if job.state.is_finished() {
+                txn.open_table(INPUTS)?.remove(id)?;
+            }
+
+            job
+        };
+        txn.commit()?;
+
+        Ok(job)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, io};
+
+    use zktls_input_builder::RequestBuilder;
+
+    use super::*;
+
+    fn request() -> TemplatedRequest {
+        RequestBuilder::new("https://example.com/")
+            .unwrap()
+            .build()
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn test_retry_and_resume() {
+        let path = env::temp_dir().join(format!("zktls-job-queue-{}.redb", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let queue = JobQueue::open(&path)
+            .unwrap()
+            .max_attempts(2)
+            .backoff(Duration::ZERO);
+        let first = queue.push(request(), ProofMode::Groth16).unwrap();
+        let second = queue.push(request(), ProofMode::Core).unwrap();
+        assert_eq!(second.id, first.id + 1);
+
+        let job = queue.claim().unwrap().unwrap();
+        assert_eq!(job.id, first.id);
+        assert_eq!(job.attempts, 1);
+
+        // A retryable failure puts the job back in the queue until it runs out of attempts.
+        let e = io::Error::from(io::ErrorKind::ConnectionReset).into();
+        assert_eq!(queue.fail(job.id, &e).unwrap().state, JobState::Pending);
+        let job = queue.claim().unwrap().unwrap();
+        assert_eq!((job.id, job.attempts), (first.id, 2));
+        let txn = queue.db.begin_write().unwrap();
+        txn.open_table(INPUTS)
+            .unwrap()
+            .insert(job.id, b"input".as_slice())
+            .unwrap();
+        txn.commit().unwrap();
+        assert_eq!(queue.fail(job.id, &e).unwrap().state, JobState::Failed);
+        assert!(queue.input(job.id).unwrap().is_none());
+
+        // The second job is interrupted while running, and resumed on reopen.
+        let job = queue.claim().unwrap().unwrap();
+        assert_eq!(job.id, second.id);
+        drop(queue);
+
+        let queue = JobQueue::open(&path).unwrap();
+        assert_eq!(
+            queue.get(second.id).unwrap().unwrap().state,
+            JobState::Pending
+        );
+        assert_eq!(queue.claim().unwrap().unwrap().id, second.id);
+        assert!(queue.claim().unwrap().is_none());
+        assert_eq!(queue.list().unwrap().len(), 2);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_claim_due_jobs() {
+        let path = env::temp_dir().join(format!("zktls-job-queue-due-{}.redb", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let queue = JobQueue::open(&path)
+            .unwrap()
+            .backoff(Duration::from_secs(3600));
+        let first = queue.push(request(), ProofMode::Groth16).unwrap();
+        let second = queue.push(request(), ProofMode::Groth16).unwrap();
+
+        // The retry of the first job is not due before the second job.
+        let job = queue.claim().unwrap().unwrap();
+        let e = io::Error::from(io::ErrorKind::ConnectionReset).into();
+        assert_eq!(queue.fail(job.id, &e).unwrap().state, JobState::Pending);
+        assert_eq!(queue.claim().unwrap().unwrap().id, second.id);
+        assert!(queue.claim().unwrap().is_none());
+        drop(queue);
+
+        // The index of the pending jobs is rebuilt on reopen, with the
+        // interrupted second job due at once.
+        let queue = JobQueue::open(&path).unwrap();
+        assert_eq!(queue.claim().unwrap().unwrap().id, second.id);
+        assert!(queue.claim().unwrap().is_none());
+        assert_eq!(
+            queue.get(first.id).unwrap().unwrap().state,
+            JobState::Pending
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,4 @@
+//! In-process TLS server for hermetic tests of the zktls input builder.
+
+mod server;
+pub use server::*;
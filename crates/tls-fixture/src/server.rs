@@ -0,0 +1,179 @@
+use std::{
+    collections::VecDeque,
+    io::{Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use anyhow::Result;
+use rcgen::{BasicConstraints, CertificateParams, DnType, IsCa, KeyPair};
+use rustls::{
+    pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer},
+    ServerConfig, ServerConnection,
+};
+use zktls_program_core::Request;
+
+pub const SERVER_NAME: &str = "localhost";
+
+#[derive(Default)]
+struct Script {
+    responses: VecDeque<Vec<u8>>,
+    requests: Vec<Vec<u8>>,
+}
+
+/// TLS server on localhost, signed by a CA generated on start.
+///
+/// Every connection reads one HTTP request and answers with the next scripted
+/// response, then closes the connection. The last response is repeated once the
+/// script is exhausted.
+pub struct FixtureServer {
+    addr: SocketAddr,
+    ca_certificate: CertificateDer<'static>,
+    certificate: CertificateDer<'static>,
+    script: Arc<Mutex<Script>>,
+}
+
+impl FixtureServer {
+    pub fn start(responses: impl IntoIterator<Item = Vec<u8>>) -> Result<Self> {
+        let ca_key = KeyPair::generate()?;
+        let mut ca_params = CertificateParams::new(Vec::<String>::new())?;
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        ca_params
+            .distinguished_name
+            .push(DnType::CommonName, "zktls fixture CA");
+        let ca = ca_params.self_signed(&ca_key)?;
+
+        let key = KeyPair::generate()?;
+        let cert =
+            CertificateParams::new(vec![SERVER_NAME.to_string()])?.signed_by(&key, &ca, &ca_key)?;
+
+        let config = ServerConfig::builder_with_provider(Arc::new(rustls_rustcrypto::provider()))
+            .with_safe_default_protocol_versions()?
+            .with_no_client_auth()
+            .with_single_cert(
+                vec![cert.der().clone(), ca.der().clone()],
+                PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key.serialize_der())),
+            )?;
+        let config = Arc::new(config);
+
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+
+        let script = Arc::new(Mutex::new(Script {
+            responses: responses.into_iter().collect(),
+            requests: Vec::new(),
+        }));
+
+        let server_script = script.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let res = stream
+                    .map_err(Into::into)
+                    .and_then(|stream| serve(config.clone(), stream, &server_script));
+
+                if let Err(e) = res {
+                    log::warn!("fixture server connection failed: {:?}", e);
+                }
+            }
+        });
+
+        log::info!("fixture server listening on {}", addr);
+
+        Ok(Self {
+            addr,
+            ca_certificate: ca.der().clone(),
+            certificate: cert.der().clone(),
+            script,
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Certificate of the CA which signed the server certificate.
+    pub fn ca_certificate(&self) -> CertificateDer<'static> {
+        self.ca_certificate.clone()
+    }
+
+    /// Certificate of the server, issued by the CA.
+    pub fn certificate(&self) -> CertificateDer<'static> {
+        self.certificate.clone()
+    }
+
+    /// Raw HTTP requests received so far.
+    pub fn requests(&self) -> Vec<Vec<u8>> {
+        self.script.lock().expect("script lock").requests.clone()
+    }
+
+    /// Point `request` to this server, sending `http` as the raw HTTP request.
+    pub fn request(&self, mut request: Request, http: &[u8]) -> Request {
+        request.request_info.request = http.to_vec().into();
+        request.request_info.remote_addr = self.addr.to_string();
+        request.request_info.server_name = SERVER_NAME.to_string();
+
+        request
+    }
+}
+
+fn serve(config: Arc<ServerConfig>, mut stream: TcpStream, script: &Mutex<Script>) -> Result<()> {
+    let mut conn = ServerConnection::new(config)?;
+    let mut tls = rustls::Stream::new(&mut conn, &mut stream);
+
+    let request = read_http_request(&mut tls)?;
+
+    let response = {
+        let mut script = script.lock().expect("script lock");
+        script.requests.push(request);
+
+        match script.responses.len() {
+            0 => return Err(anyhow::anyhow!("no scripted response left")),
+            1 => script.responses[0].clone(),
+            _ => script.responses.pop_front().expect("response exists"),
+        }
+    };
+
+    tls.write_all(&response)?;
+    tls.flush()?;
+
+    conn.send_close_notify();
+    conn.complete_io(&mut stream)?;
+
+    Ok(())
+}
+
+/// Read the request head, and the body if it has a `Content-Length`.
+fn read_http_request(tls: &mut impl Read) -> Result<Vec<u8>> {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 4096];
+
+    let head_end = loop {
+        if let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+
+        let n = tls.read(&mut buf)?;
+        if n == 0 {
+            return Err(anyhow::anyhow!("connection closed before request head"));
+        }
+        request.extend_from_slice(&buf[..n]);
+    };
+
+    let content_length = String::from_utf8_lossy(&request[..head_end])
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+
+    while request.len() < head_end + content_length {
+        let n = tls.read(&mut buf)?;
+        if n == 0 {
+            return Err(anyhow::anyhow!("connection closed before request body"));
+        }
+        request.extend_from_slice(&buf[..n]);
+    }
+
+    Ok(request)
+}
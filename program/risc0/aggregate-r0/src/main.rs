@@ -0,0 +1,30 @@
+//! Aggregation program of the RISC0 backend.
+//!
+//! The prover writes the `AggregationInput` and adds the succinct receipts as
+//! assumptions, which `env::verify` resolves.
+
+#![no_main]
+risc0_zkvm::guest::entry!(main);
+
+use risc0_zkvm::guest::env;
+use zktls_aggregation::{run_aggregation, AggregationEnv, AggregationInput};
+
+struct Risc0Env;
+
+impl AggregationEnv for Risc0Env {
+    fn read_input(&mut self) -> AggregationInput {
+        env::read()
+    }
+
+    fn verify(&mut self, program: &[u32; 8], public_output: &[u8]) {
+        env::verify(*program, public_output).expect("no receipt for the public output");
+    }
+
+    fn commit(&mut self, output: &[u8]) {
+        env::commit_slice(output);
+    }
+}
+
+fn main() {
+    run_aggregation(&mut Risc0Env);
+}
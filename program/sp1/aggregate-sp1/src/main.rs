@@ -0,0 +1,31 @@
+//! Aggregation program of the SP1 backend.
+//!
+//! The prover writes the `AggregationInput` to the stdin, followed by the
+//! compressed proofs, which `verify_sp1_proof` consumes in order.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use sha2::{Digest, Sha256};
+use zktls_aggregation::{run_aggregation, AggregationEnv, AggregationInput};
+
+struct Sp1Env;
+
+impl AggregationEnv for Sp1Env {
+    fn read_input(&mut self) -> AggregationInput {
+        sp1_zkvm::io::read()
+    }
+
+    fn verify(&mut self, program: &[u32; 8], public_output: &[u8]) {
+        let digest: [u8; 32] = Sha256::digest(public_output).into();
+        sp1_zkvm::lib::verify::verify_sp1_proof(program, &digest);
+    }
+
+    fn commit(&mut self, output: &[u8]) {
+        sp1_zkvm::io::commit_slice(output);
+    }
+}
+
+pub fn main() {
+    run_aggregation(&mut Sp1Env);
+}